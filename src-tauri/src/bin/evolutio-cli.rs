@@ -0,0 +1,791 @@
+//! Headless front end to the simulation engine, for scripted experiments.
+//!
+//! Parameters come from flags, from a JSON `--config` file, or both (flags win).
+//! Results are written as JSON or CSV to stdout or to `--output`.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{ self, BufWriter, Write };
+use std::path::PathBuf;
+
+use clap::{ Args, Parser, Subcommand, ValueEnum };
+use serde::{ Deserialize, Serialize };
+
+use evolutio_lib::registry::default_roster;
+use evolutio_lib::spatial::SpatialGrid;
+use evolutio_lib::validation::{ check_grid_size, check_probability };
+use evolutio_lib::{
+    build_strategy,
+    check_strategy,
+    estimate_fixation,
+    exact_game,
+    list_strategies,
+    memory_one_of,
+    play_match,
+    run_evolution,
+    run_moran,
+    run_tournament,
+    seeded_rng,
+    Dynamics,
+    EngineError,
+    EvolutionConfig,
+    FsmDefinition,
+    MatchConfig,
+    MoranConfig,
+    NoiseSchedule,
+    Pairing,
+    PayoffMatrix,
+    StrategySpec,
+    TournamentConfig,
+};
+
+#[derive(Parser)]
+#[command(name = "evolutio-cli", about = "Run Evolutio simulations without the GUI")]
+struct Cli {
+    /// JSON file with parameters; any flag given on the command line overrides it
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[arg(long, global = true, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
+    /// Write results here instead of stdout
+    #[arg(long, short, global = true)]
+    output: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play a single match between two strategies
+    Game(GameArgs),
+    /// Round-robin tournament over a roster of strategies
+    Tournament(TournamentArgs),
+    /// Population evolution over the built-in strategies
+    Evolve(EvolveArgs),
+    /// One Moran birth-death run until a single strategy has fixated
+    Moran(MoranRunArgs),
+    /// Estimate the probability that one mutant takes over a resident population
+    Fixation(FixationArgs),
+    /// Cooperate/defect imitation dynamics on a toroidal grid
+    Spatial(SpatialArgs),
+    /// List the registered strategies and their parameters
+    Strategies,
+    /// Cooperation probabilities of a zero-determinant strategy under a payoff matrix
+    Zd(ZdArgs),
+}
+
+// Every parameter is optional so that flags can be layered over a config file.
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct MatchArgs {
+    /// Rounds per match (the cap when --continuation is set) [default: 200]
+    #[arg(long)]
+    rounds: Option<u32>,
+    /// Probability that each move is flipped [default: 0]
+    #[arg(long)]
+    noise: Option<f64>,
+    /// Execution noise of the first player only, overriding --noise; in evolve and Moran runs,
+    /// of the strategy whose payoff is being measured
+    #[arg(long)]
+    player_noise: Option<f64>,
+    /// Execution noise of the second player only, overriding --noise
+    #[arg(long)]
+    opponent_noise: Option<f64>,
+    /// Execution noise over time, e.g. "ramp(start=0,end=100,to=0.2)" or
+    /// "shock(start=50,length=10,rate=0.5)" with an optional every=N to repeat the burst
+    #[arg(long, value_parser = parse_noise_schedule)]
+    noise_schedule: Option<NoiseSchedule>,
+    /// Probability that each player misreads each opponent move in its own history [default: 0]
+    #[arg(long)]
+    perception_noise: Option<f64>,
+    /// Payoff matrix as T,R,P,S [default: 5,3,1,0]
+    #[arg(long, value_parser = parse_payoff)]
+    payoff: Option<PayoffMatrix>,
+    /// Probability that another round follows each round
+    #[arg(long)]
+    continuation: Option<f64>,
+    /// Discount factor for the discounted scores of game and tournament; evolution and Moran
+    /// fitness is always the undiscounted per-round payoff
+    #[arg(long)]
+    discount: Option<f64>,
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+impl MatchArgs {
+    fn or(self, file: MatchArgs) -> Self {
+        Self {
+            rounds: self.rounds.or(file.rounds),
+            noise: self.noise.or(file.noise),
+            player_noise: self.player_noise.or(file.player_noise),
+            opponent_noise: self.opponent_noise.or(file.opponent_noise),
+            noise_schedule: self.noise_schedule.or(file.noise_schedule),
+            perception_noise: self.perception_noise.or(file.perception_noise),
+            payoff: self.payoff.or(file.payoff),
+            continuation: self.continuation.or(file.continuation),
+            discount: self.discount.or(file.discount),
+            seed: self.seed.or(file.seed),
+        }
+    }
+
+    fn match_config(&self) -> Result<MatchConfig, EngineError> {
+        let defaults = MatchConfig::default();
+        let config = MatchConfig {
+            rounds: self.rounds.unwrap_or(defaults.rounds),
+            noise: self.noise.unwrap_or(defaults.noise),
+            player_noise: self.player_noise,
+            opponent_noise: self.opponent_noise,
+            noise_schedule: self.noise_schedule.unwrap_or_default(),
+            perception_noise: self.perception_noise.unwrap_or(defaults.perception_noise),
+            payoff_matrix: self.payoff.unwrap_or(defaults.payoff_matrix),
+            continuation: self.continuation,
+            discount: self.discount,
+        };
+        config.validate()?;
+        warn_if_not_dilemma(&config.payoff_matrix);
+        Ok(config)
+    }
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct GameArgs {
+    /// First player, e.g. "tit_for_tat" or "memory_one(p_cd=0.3,p_dd=0.3)"
+    #[arg(long)]
+    p1: Option<StrategySpec>,
+    #[arg(long)]
+    p2: Option<StrategySpec>,
+    /// Compute the exact expected outcome instead of playing (memory-one strategies only)
+    #[arg(long)]
+    exact: bool,
+    #[command(flatten)]
+    #[serde(flatten)]
+    common: MatchArgs,
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct TournamentArgs {
+    /// Competing strategy, repeatable; duplicates and variants like "joss(sneakiness=0.2)" allowed
+    /// [default: each strategy of the default field once, as marked by `strategies`]
+    #[arg(long)]
+    roster: Option<Vec<StrategySpec>>,
+    /// State-machine strategy to add to the roster, as a JSON or TOML file; repeatable
+    #[arg(long)]
+    machine: Option<Vec<PathBuf>>,
+    /// Independent replays of the tournament, summarised as mean, spread and win rate [default: 1]
+    #[arg(long)]
+    repetitions: Option<u32>,
+    /// Whether each entry also plays a copy of itself [default: true]
+    #[arg(long)]
+    self_play: Option<bool>,
+    /// ordered plays each pair from both seats, unordered once scoring both players; unordered
+    /// rejects --player-noise and --opponent-noise unless they match [default: ordered]
+    #[arg(long)]
+    pairing: Option<Pairing>,
+    /// Score pairs of memory-one strategies by their exact expectation instead of playing them
+    #[arg(long)]
+    exact: bool,
+    /// With --format csv, write the pairwise cross-table instead of the ranking
+    #[arg(long)]
+    head_to_head: bool,
+    #[command(flatten)]
+    #[serde(flatten)]
+    common: MatchArgs,
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct EvolveArgs {
+    /// Number of generations to record [default: 50]
+    #[arg(long)]
+    generations: Option<u32>,
+    /// Initial counts, one per strategy of the default field in `strategies` order, comma separated
+    /// [default: 5 each]
+    #[arg(long, value_delimiter = ',')]
+    populations: Option<Vec<u32>>,
+    /// Selection dynamics [default: replicator]
+    #[arg(long, value_enum)]
+    dynamics: Option<Dynamics>,
+    /// Per-offspring probability of switching to a random other strategy (wright_fisher only) [default: 0]
+    #[arg(long)]
+    mutation_rate: Option<f64>,
+    /// Matches averaged into each expected payoff for stochastic pairings [default: 1]
+    #[arg(long)]
+    payoff_repetitions: Option<u32>,
+    /// Replay the pairings every generation instead of computing the payoff matrix once
+    #[arg(long)]
+    resample: bool,
+    /// Compute payoffs between memory-one strategies exactly instead of sampling (not with --resample)
+    #[arg(long)]
+    exact: bool,
+    #[command(flatten)]
+    #[serde(flatten)]
+    common: MatchArgs,
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct MoranArgs {
+    /// Selection intensity w in fitness = 1 - w + w * payoff [default: 1]
+    #[arg(long)]
+    selection_intensity: Option<f64>,
+    /// Birth-death steps before a run is reported as unresolved [default: 10000000]
+    #[arg(long)]
+    max_steps: Option<u64>,
+    /// Matches averaged into each expected payoff for stochastic pairings [default: 1]
+    #[arg(long)]
+    payoff_repetitions: Option<u32>,
+    /// Compute payoffs between memory-one strategies exactly instead of sampling
+    #[arg(long)]
+    exact: bool,
+    #[command(flatten)]
+    #[serde(flatten)]
+    common: MatchArgs,
+}
+
+impl MoranArgs {
+    fn or(self, file: MoranArgs) -> Self {
+        Self {
+            selection_intensity: self.selection_intensity.or(file.selection_intensity),
+            max_steps: self.max_steps.or(file.max_steps),
+            payoff_repetitions: self.payoff_repetitions.or(file.payoff_repetitions),
+            exact: self.exact || file.exact,
+            common: self.common.or(file.common),
+        }
+    }
+
+    fn moran_config(&self) -> MoranConfig {
+        let defaults = MoranConfig::default();
+        MoranConfig {
+            selection_intensity: self.selection_intensity.unwrap_or(defaults.selection_intensity),
+            max_steps: self.max_steps.unwrap_or(defaults.max_steps),
+            payoff_repetitions: self.payoff_repetitions.unwrap_or(defaults.payoff_repetitions),
+            exact: self.exact,
+        }
+    }
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct MoranRunArgs {
+    /// Initial counts, one per strategy of the default field in `strategies` order, comma separated
+    /// [default: 5 each]
+    #[arg(long, value_delimiter = ',')]
+    populations: Option<Vec<u32>>,
+    #[command(flatten)]
+    #[serde(flatten)]
+    moran: MoranArgs,
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct FixationArgs {
+    /// Strategy id of the resident population
+    #[arg(long)]
+    resident: Option<String>,
+    /// Strategy id of the single invading mutant
+    #[arg(long)]
+    mutant: Option<String>,
+    /// Total population size, mutant included [default: 100]
+    #[arg(long)]
+    population_size: Option<u32>,
+    /// Independent runs to average over [default: 1000]
+    #[arg(long)]
+    repetitions: Option<u32>,
+    #[command(flatten)]
+    #[serde(flatten)]
+    moran: MoranArgs,
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct ZdArgs {
+    /// Extortion factor χ ≥ 1 [default: 3]
+    #[arg(long)]
+    slope: Option<f64>,
+    /// Baseline payoff, given as a fraction of the way from P (0, extortion) to R (1, generous)
+    /// rather than as a payoff, so it means the same under any matrix [default: 0]
+    #[arg(long)]
+    baseline: Option<f64>,
+    /// Fraction of the largest feasible step towards the enforced line, above 0 [default: 1]
+    #[arg(long)]
+    scale: Option<f64>,
+    /// Payoff matrix as T,R,P,S [default: 5,3,1,0]
+    #[arg(long, value_parser = parse_payoff)]
+    payoff: Option<PayoffMatrix>,
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct SpatialArgs {
+    /// Grid width [default: 50]
+    #[arg(long)]
+    width: Option<usize>,
+    /// Grid height [default: 50]
+    #[arg(long)]
+    height: Option<usize>,
+    /// Number of generations to step [default: 100]
+    #[arg(long)]
+    generations: Option<u32>,
+    /// Probability that a cell flips the strategy it imitates [default: 0]
+    #[arg(long)]
+    noise: Option<f64>,
+    /// Payoff matrix as T,R,P,S [default: 5,3,1,0]
+    #[arg(long, value_parser = parse_payoff)]
+    payoff: Option<PayoffMatrix>,
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct SpatialSnapshot {
+    generation: u32,
+    cooperators: usize,
+    defectors: usize,
+}
+
+fn parse_payoff(s: &str) -> Result<PayoffMatrix, String> {
+    let values: Vec<i32> = s
+        .split(',')
+        .map(|v| v.trim().parse::<i32>().map_err(|e| format!("{:?}: {}", v, e)))
+        .collect::<Result<_, _>>()?;
+    match values[..] {
+        [t, r, p, s] => Ok(PayoffMatrix { t, r, p, s }),
+        _ => Err(format!("expected four values T,R,P,S, got {}", values.len())),
+    }
+}
+
+/// Reads a schedule in the same name(key=value,...) form as a strategy spec.
+fn parse_noise_schedule(s: &str) -> Result<NoiseSchedule, String> {
+    let spec: StrategySpec = s.parse().map_err(|e: EngineError| e.to_string())?;
+    let valid: &[&str] = match spec.id.as_str() {
+        "constant" => &[],
+        "ramp" => &["start", "end", "to"],
+        "shock" => &["start", "length", "rate", "every"],
+        other => {
+            return Err(format!("unknown schedule {:?}, expected constant, ramp or shock", other));
+        }
+    };
+    if let Some(key) = spec.params.keys().find(|k| !valid.contains(&k.as_str())) {
+        return Err(format!("{} has no parameter {:?}, expected one of {:?}", spec.id, key, valid));
+    }
+    let get = |key: &str| spec.params.get(key).copied().ok_or_else(|| format!("{} needs {}", spec.id, key));
+    // round counts must be whole and in range; `as u32` would quietly saturate or truncate them
+    let round = |key: &str, value: f64| {
+        if value.fract() == 0.0 && (0.0..=(u32::MAX as f64)).contains(&value) {
+            Ok(value as u32)
+        } else {
+            Err(format!("{} {} must be a whole number of rounds, got {}", spec.id, key, value))
+        }
+    };
+    let count = |key: &str| round(key, get(key)?);
+    match spec.id.as_str() {
+        "ramp" => Ok(NoiseSchedule::Ramp {
+            start: count("start")?,
+            end: count("end")?,
+            to: get("to")?,
+        }),
+        "shock" => Ok(NoiseSchedule::Shock {
+            start: count("start")?,
+            length: count("length")?,
+            rate: get("rate")?,
+            every: spec.params
+                .get("every")
+                .map(|&e| round("every", e))
+                .transpose()?,
+        }),
+        _ => Ok(NoiseSchedule::Constant),
+    }
+}
+
+fn read_config<T: for<'de> Deserialize<'de> + Default>(
+    path: &Option<PathBuf>
+) -> Result<T, Box<dyn Error>> {
+    match path {
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            Ok(serde_json::from_reader(file).map_err(|e| format!("{}: {}", path.display(), e))?)
+        }
+        None => Ok(T::default()),
+    }
+}
+
+fn read_machine(path: &PathBuf) -> Result<StrategySpec, Box<dyn Error>> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let machine = FsmDefinition::parse(&source).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(StrategySpec::machine(machine))
+}
+
+fn csv_quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+fn write_json<T: Serialize>(out: &mut dyn Write, value: &T) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Non-PD matrices are legitimate experiments, so they only get a note on stderr.
+fn warn_if_not_dilemma(payoff: &PayoffMatrix) {
+    for warning in payoff.dilemma_warnings() {
+        eprintln!("evolutio-cli: warning: {}", warning.message);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let mut out: Box<dyn Write> = match &cli.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    match cli.command {
+        Command::Game(args) => {
+            let file: GameArgs = read_config(&cli.config)?;
+            let p1_spec = args.p1.or(file.p1).ok_or("missing --p1")?;
+            let p2_spec = args.p2.or(file.p2).ok_or("missing --p2")?;
+            let common = args.common.or(file.common);
+            let config = common.match_config()?;
+            check_strategy(&p1_spec, &config.payoff_matrix)?;
+            check_strategy(&p2_spec, &config.payoff_matrix)?;
+
+            if args.exact || file.exact {
+                let exact = exact_game(&p1_spec, &p2_spec, config)?;
+                match cli.format {
+                    Format::Json => write_json(&mut out, &exact)?,
+                    Format::Csv => {
+                        writeln!(
+                            out,
+                            "expected_rounds,player_score,opponent_score,player_per_round,opponent_per_round,player_cooperation,opponent_cooperation"
+                        )?;
+                        writeln!(
+                            out,
+                            "{},{},{},{},{},{},{}",
+                            exact.expected_rounds,
+                            exact.player_score,
+                            exact.opponent_score,
+                            exact.player_per_round,
+                            exact.opponent_per_round,
+                            exact.player_cooperation,
+                            exact.opponent_cooperation
+                        )?;
+                    }
+                }
+            } else {
+                let mut p1 = build_strategy(&p1_spec)?;
+                let mut p2 = build_strategy(&p2_spec)?;
+                let mut rng = seeded_rng(common.seed);
+                let result = play_match(p1.as_mut(), p2.as_mut(), config, &mut rng);
+
+                match cli.format {
+                    Format::Json => write_json(&mut out, &result)?,
+                    Format::Csv => {
+                        writeln!(out, "round,player,opponent")?;
+                        for (i, (a1, a2)) in result.rounds.iter().enumerate() {
+                            writeln!(out, "{},{:?},{:?}", i + 1, a1, a2)?;
+                        }
+                    }
+                }
+            }
+        }
+        Command::Tournament(args) => {
+            let file: TournamentArgs = read_config(&cli.config)?;
+            let mut roster = args.roster.or(file.roster).unwrap_or_default();
+            let machines = args.machine.or(file.machine).unwrap_or_default();
+            if roster.is_empty() && !machines.is_empty() {
+                roster = default_roster();
+            }
+            for path in &machines {
+                roster.push(read_machine(path)?);
+            }
+            let tournament = TournamentConfig {
+                repetitions: args.repetitions.or(file.repetitions).unwrap_or(1),
+                self_play: args.self_play.or(file.self_play).unwrap_or(true),
+                pairing: args.pairing.or(file.pairing).unwrap_or_default(),
+                exact: args.exact || file.exact,
+            };
+            let common = args.common.or(file.common);
+            let result = run_tournament(
+                &roster,
+                common.match_config()?,
+                tournament,
+                &mut seeded_rng(common.seed)
+            )?;
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &result)?,
+                Format::Csv if args.head_to_head || file.head_to_head => {
+                    let table = &result.cross_table;
+                    writeln!(out, "strategy,opponent,mean_payoff,cooperation_rate,wins,losses,draws")?;
+                    for (i, name) in table.names.iter().enumerate() {
+                        for (j, opponent) in table.names.iter().enumerate() {
+                            if i == j && !tournament.self_play {
+                                continue;
+                            }
+                            let o = table.outcomes[i][j];
+                            writeln!(
+                                out,
+                                "{},{},{},{},{},{},{}",
+                                csv_quote(name),
+                                csv_quote(opponent),
+                                table.payoffs[i][j],
+                                table.cooperation[i][j],
+                                o.wins,
+                                o.losses,
+                                o.draws
+                            )?;
+                        }
+                    }
+                }
+                Format::Csv => {
+                    writeln!(
+                        out,
+                        "rank,strategy,score,discounted_score,per_round,normalised,mean,std_dev,ci95_low,ci95_high,median_rank,win_rate"
+                    )?;
+                    for (i, ((name, score), stats)) in result.ranking.iter().zip(&result.stats).enumerate() {
+                        let discounted = result.discounted_scores
+                            .as_ref()
+                            .map(|d| d[i].to_string())
+                            .unwrap_or_default();
+                        writeln!(
+                            out,
+                            "{},{},{},{},{},{},{},{},{},{},{},{}",
+                            i + 1,
+                            csv_quote(name),
+                            score,
+                            discounted,
+                            stats.per_round,
+                            stats.normalised
+                                .map(|x| x.to_string())
+                                .unwrap_or_default(),
+                            stats.mean,
+                            stats.std_dev,
+                            stats.ci95.0,
+                            stats.ci95.1,
+                            stats.median_rank,
+                            stats.win_rate
+                        )?;
+                    }
+                }
+            }
+        }
+        Command::Evolve(args) => {
+            let file: EvolveArgs = read_config(&cli.config)?;
+            let generations = args.generations.or(file.generations).unwrap_or(50);
+            let populations = args.populations.or(file.populations).unwrap_or_default();
+            let evolution = EvolutionConfig {
+                dynamics: args.dynamics.or(file.dynamics).unwrap_or_default(),
+                mutation_rate: args.mutation_rate.or(file.mutation_rate).unwrap_or(0.0),
+                payoff_repetitions: args.payoff_repetitions.or(file.payoff_repetitions).unwrap_or(1),
+                resample_each_generation: args.resample || file.resample,
+                exact: args.exact || file.exact,
+            };
+            let common = args.common.or(file.common);
+
+            let history = run_evolution(
+                common.match_config()?,
+                evolution,
+                populations,
+                generations,
+                &mut seeded_rng(common.seed)
+            )?;
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &history)?,
+                Format::Csv => {
+                    if let Some(first) = history.first() {
+                        let names: Vec<String> = first.shares
+                            .iter()
+                            .map(|(name, _)| csv_quote(name))
+                            .collect();
+                        writeln!(out, "generation,{}", names.join(","))?;
+                    }
+                    for gen in &history {
+                        let shares: Vec<String> = gen.shares
+                            .iter()
+                            .map(|(_, share)| share.to_string())
+                            .collect();
+                        writeln!(out, "{},{}", gen.gen_number, shares.join(","))?;
+                    }
+                }
+            }
+        }
+        Command::Moran(args) => {
+            let file: MoranRunArgs = read_config(&cli.config)?;
+            let populations = args.populations.or(file.populations).unwrap_or_default();
+            let moran = args.moran.or(file.moran);
+
+            let run = run_moran(
+                moran.common.match_config()?,
+                moran.moran_config(),
+                populations,
+                &mut seeded_rng(moran.common.seed)
+            )?;
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &run)?,
+                Format::Csv => {
+                    writeln!(out, "strategy,final_count")?;
+                    for (name, count) in &run.final_populations {
+                        writeln!(out, "{},{}", csv_quote(name), count)?;
+                    }
+                }
+            }
+        }
+        Command::Fixation(args) => {
+            let file: FixationArgs = read_config(&cli.config)?;
+            let resident = args.resident.or(file.resident).ok_or("missing --resident")?;
+            let mutant = args.mutant.or(file.mutant).ok_or("missing --mutant")?;
+            let population_size = args.population_size.or(file.population_size).unwrap_or(100);
+            let repetitions = args.repetitions.or(file.repetitions).unwrap_or(1000);
+            let moran = args.moran.or(file.moran);
+
+            let estimate = estimate_fixation(
+                moran.common.match_config()?,
+                moran.moran_config(),
+                &resident,
+                &mutant,
+                population_size,
+                repetitions,
+                &mut seeded_rng(moran.common.seed)
+            )?;
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &estimate)?,
+                Format::Csv => {
+                    writeln!(
+                        out,
+                        "resident,mutant,population_size,repetitions,mutant_fixations,resident_fixations,unresolved,fixation_probability,neutral_probability,mean_fixation_time"
+                    )?;
+                    writeln!(
+                        out,
+                        "{},{},{},{},{},{},{},{},{},{}",
+                        csv_quote(&estimate.resident),
+                        csv_quote(&estimate.mutant),
+                        estimate.population_size,
+                        estimate.repetitions,
+                        estimate.mutant_fixations,
+                        estimate.resident_fixations,
+                        estimate.unresolved,
+                        estimate.fixation_probability,
+                        estimate.neutral_probability,
+                        estimate.mean_fixation_time
+                            .map(|t| t.to_string())
+                            .unwrap_or_default()
+                    )?;
+                }
+            }
+        }
+        Command::Spatial(args) => {
+            let file: SpatialArgs = read_config(&cli.config)?;
+            let width = args.width.or(file.width).unwrap_or(50);
+            let height = args.height.or(file.height).unwrap_or(50);
+            let generations = args.generations.or(file.generations).unwrap_or(100);
+            let noise = args.noise.or(file.noise).unwrap_or(0.0);
+            let payoff = args.payoff.or(file.payoff).unwrap_or(MatchConfig::default().payoff_matrix);
+            check_grid_size(width, height)?;
+            check_probability("noise", noise)?;
+            payoff.validate()?;
+            warn_if_not_dilemma(&payoff);
+            let mut rng = seeded_rng(args.seed.or(file.seed));
+
+            let mut grid = SpatialGrid::new_coin_flip(width, height, &mut rng);
+            let mut snapshots = Vec::with_capacity((generations as usize) + 1);
+            for generation in 0..=generations {
+                if generation > 0 {
+                    grid.step(&payoff, noise, &mut rng);
+                }
+                let cooperators = grid.cooperator_count();
+                snapshots.push(SpatialSnapshot {
+                    generation,
+                    cooperators,
+                    defectors: grid.cells.len() - cooperators,
+                });
+            }
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &snapshots)?,
+                Format::Csv => {
+                    writeln!(out, "generation,cooperators,defectors")?;
+                    for s in &snapshots {
+                        writeln!(out, "{},{},{}", s.generation, s.cooperators, s.defectors)?;
+                    }
+                }
+            }
+        }
+        Command::Zd(args) => {
+            let file: ZdArgs = read_config(&cli.config)?;
+            let payoff = args.payoff.or(file.payoff).unwrap_or(MatchConfig::default().payoff_matrix);
+            payoff.validate()?;
+            let params = [
+                ("slope", args.slope.or(file.slope)),
+                ("baseline", args.baseline.or(file.baseline)),
+                ("scale", args.scale.or(file.scale)),
+            ]
+                .into_iter()
+                .filter_map(|(name, value)| value.map(|v| (name.to_string(), v)))
+                .collect();
+            let spec = StrategySpec { params, ..StrategySpec::new("zero_determinant") };
+            check_strategy(&spec, &payoff)?;
+            let m = memory_one_of(&spec, &payoff)?.expect("zero-determinant strategies are memory-one");
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &m)?,
+                Format::Csv => {
+                    writeln!(out, "p_first,p_cc,p_cd,p_dc,p_dd")?;
+                    writeln!(out, "{},{},{},{},{}", m.p_first, m.p_cc, m.p_cd, m.p_dc, m.p_dd)?;
+                }
+            }
+        }
+        Command::Strategies => {
+            let strategies = list_strategies();
+
+            match cli.format {
+                Format::Json => write_json(&mut out, &strategies)?,
+                Format::Csv => {
+                    writeln!(out, "id,name,tags,default_field,params,description")?;
+                    for s in &strategies {
+                        let tags: Vec<&str> = s.tags
+                            .iter()
+                            .map(|t| t.as_str())
+                            .collect();
+                        let params: Vec<String> = s.params
+                            .iter()
+                            .map(|p| format!("{}={}", p.name, p.default))
+                            .collect();
+                        writeln!(
+                            out,
+                            "{},{},{},{},{},{}",
+                            s.id,
+                            csv_quote(s.name),
+                            tags.join(" "),
+                            s.default_field,
+                            params.join(" "),
+                            csv_quote(s.description)
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("evolutio-cli: {}", e);
+        std::process::exit(1);
+    }
+}
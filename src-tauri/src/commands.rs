@@ -0,0 +1,418 @@
+use std::sync::Mutex;
+
+use crate::spatial::{ SpatialGrid, Strategy as SpatialStrategy };
+use crate::validation::{ check_grid_size, check_probability };
+use crate::{
+    build_strategy,
+    check_strategy,
+    evolution,
+    markov,
+    memory_one_of,
+    moran,
+    registry,
+    EngineError,
+    play_match,
+    seeded_rng,
+    tournament,
+    Dynamics,
+    EvolutionConfig,
+    ExactMatch,
+    FixationEstimate,
+    FsmDefinition,
+    Generation,
+    MatchConfig,
+    MatchResult,
+    MemoryOne,
+    MoranConfig,
+    MoranRun,
+    NoiseSchedule,
+    Pairing,
+    PayoffMatrix,
+    PayoffWarning,
+    StrategyInfo,
+    StrategySpec,
+    TournamentConfig,
+    TournamentResult,
+};
+use rand::prelude::*;
+
+pub struct GameState {
+    pub spatial_grid: Mutex<SpatialGrid<SpatialStrategy>>,
+    // seeded by init_spatial_grid and drawn from by every step, so a seeded run replays exactly
+    pub spatial_rng: Mutex<StdRng>,
+}
+
+fn poisoned<T>(e: std::sync::PoisonError<T>) -> EngineError {
+    EngineError::StateUnavailable(e.to_string())
+}
+
+#[tauri::command]
+fn list_strategies() -> Vec<StrategyInfo> {
+    registry::list_strategies()
+}
+
+/// Cooperation probabilities of a zero-determinant strategy under this matrix, or why none exist.
+/// Parameters are range-checked like those of a `zero_determinant(...)` roster spec.
+/// `baseline` is not a payoff but a fraction of the way from P (0, extortion) to R (1, generous),
+/// so the same value means the same thing under any matrix; `scale` is a fraction of the
+/// largest feasible step towards the enforced line.
+#[tauri::command]
+fn zero_determinant(
+    payoff_matrix: PayoffMatrix,
+    slope: f64,
+    baseline: Option<f64>,
+    scale: Option<f64>
+) -> Result<MemoryOne, EngineError> {
+    payoff_matrix.validate()?;
+    let params = [("slope", Some(slope)), ("baseline", baseline), ("scale", scale)]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| (name.to_string(), v)))
+        .collect();
+    let spec = StrategySpec { params, ..StrategySpec::new("zero_determinant") };
+    check_strategy(&spec, &payoff_matrix)?;
+    Ok(memory_one_of(&spec, &payoff_matrix)?.expect("zero-determinant strategies are memory-one"))
+}
+
+/// Reads a state-machine strategy from JSON or TOML text, to be added to a roster as `{ machine }`.
+#[tauri::command]
+fn parse_machine(source: String) -> Result<FsmDefinition, EngineError> {
+    FsmDefinition::parse(&source)
+}
+
+/// Range-checks the matrix and reports whether it is a Prisoner's Dilemma, for the settings panel.
+#[tauri::command]
+fn check_payoff_matrix(payoff_matrix: PayoffMatrix) -> Result<Vec<PayoffWarning>, EngineError> {
+    payoff_matrix.validate()?;
+    Ok(payoff_matrix.dilemma_warnings())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn run_game(
+    p1: StrategySpec,
+    p2: StrategySpec,
+    rounds: u32,
+    noise: f64,
+    player_noise: Option<f64>,
+    opponent_noise: Option<f64>,
+    noise_schedule: Option<NoiseSchedule>,
+    perception_noise: Option<f64>,
+    payoff_matrix: PayoffMatrix,
+    continuation: Option<f64>,
+    discount: Option<f64>,
+    seed: Option<u64>
+) -> Result<MatchResult, EngineError> {
+    let config = MatchConfig {
+        rounds,
+        noise,
+        player_noise,
+        opponent_noise,
+        noise_schedule: noise_schedule.unwrap_or_default(),
+        perception_noise: perception_noise.unwrap_or(0.0),
+        payoff_matrix,
+        continuation,
+        discount,
+    };
+    config.validate()?;
+    check_strategy(&p1, &config.payoff_matrix)?;
+    check_strategy(&p2, &config.payoff_matrix)?;
+    let mut p1 = build_strategy(&p1)?;
+    let mut p2 = build_strategy(&p2)?;
+    let mut rng = seeded_rng(seed);
+    Ok(play_match(p1.as_mut(), p2.as_mut(), config, &mut rng))
+}
+
+/// Exact expected outcome of a match between two memory-one strategies, to check `run_game` against.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn exact_game(
+    p1: StrategySpec,
+    p2: StrategySpec,
+    rounds: u32,
+    noise: f64,
+    player_noise: Option<f64>,
+    opponent_noise: Option<f64>,
+    noise_schedule: Option<NoiseSchedule>,
+    perception_noise: Option<f64>,
+    payoff_matrix: PayoffMatrix,
+    continuation: Option<f64>,
+    discount: Option<f64>
+) -> Result<ExactMatch, EngineError> {
+    let config = MatchConfig {
+        rounds,
+        noise,
+        player_noise,
+        opponent_noise,
+        noise_schedule: noise_schedule.unwrap_or_default(),
+        perception_noise: perception_noise.unwrap_or(0.0),
+        payoff_matrix,
+        continuation,
+        discount,
+    };
+    markov::exact_game(&p1, &p2, config)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn run_tournament(
+    roster: Option<Vec<StrategySpec>>,
+    rounds: u32,
+    noise: f64,
+    player_noise: Option<f64>,
+    opponent_noise: Option<f64>,
+    noise_schedule: Option<NoiseSchedule>,
+    perception_noise: Option<f64>,
+    payoff_matrix: PayoffMatrix,
+    continuation: Option<f64>,
+    discount: Option<f64>,
+    repetitions: Option<u32>,
+    self_play: Option<bool>,
+    pairing: Option<Pairing>,
+    exact: Option<bool>,
+    seed: Option<u64>
+) -> Result<TournamentResult, EngineError> {
+    let config = MatchConfig {
+        rounds,
+        noise,
+        player_noise,
+        opponent_noise,
+        noise_schedule: noise_schedule.unwrap_or_default(),
+        perception_noise: perception_noise.unwrap_or(0.0),
+        payoff_matrix,
+        continuation,
+        discount,
+    };
+    let defaults = TournamentConfig::default();
+    let tournament = TournamentConfig {
+        repetitions: repetitions.unwrap_or(defaults.repetitions),
+        self_play: self_play.unwrap_or(defaults.self_play),
+        pairing: pairing.unwrap_or(defaults.pairing),
+        exact: exact.unwrap_or(defaults.exact),
+    };
+    let roster = roster.unwrap_or_default();
+    tournament::run_tournament(&roster, config, tournament, &mut seeded_rng(seed))
+}
+
+/// Fitness is the undiscounted mean per-round payoff, so unlike the match commands this takes no
+/// `discount`. Per-player noise applies `player_noise` to the strategy being scored and
+/// `opponent_noise` to whoever it meets; the same holds for the Moran commands.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn run_evolution(
+    rounds: u32,
+    noise: f64,
+    player_noise: Option<f64>,
+    opponent_noise: Option<f64>,
+    noise_schedule: Option<NoiseSchedule>,
+    perception_noise: Option<f64>,
+    initial_populations: Vec<u32>,
+    generations: u32,
+    payoff_matrix: PayoffMatrix,
+    continuation: Option<f64>,
+    dynamics: Option<Dynamics>,
+    mutation_rate: Option<f64>,
+    payoff_repetitions: Option<u32>,
+    resample_each_generation: Option<bool>,
+    exact: Option<bool>,
+    seed: Option<u64>
+) -> Result<Vec<Generation>, EngineError> {
+    let config = MatchConfig {
+        rounds,
+        noise,
+        player_noise,
+        opponent_noise,
+        noise_schedule: noise_schedule.unwrap_or_default(),
+        perception_noise: perception_noise.unwrap_or(0.0),
+        payoff_matrix,
+        continuation,
+        ..MatchConfig::default()
+    };
+    let defaults = EvolutionConfig::default();
+    let evolution = EvolutionConfig {
+        dynamics: dynamics.unwrap_or(defaults.dynamics),
+        mutation_rate: mutation_rate.unwrap_or(defaults.mutation_rate),
+        payoff_repetitions: payoff_repetitions.unwrap_or(defaults.payoff_repetitions),
+        resample_each_generation: resample_each_generation.unwrap_or(defaults.resample_each_generation),
+        exact: exact.unwrap_or(defaults.exact),
+    };
+    evolution::run_evolution(config, evolution, initial_populations, generations, &mut seeded_rng(seed))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn run_moran(
+    rounds: u32,
+    noise: f64,
+    player_noise: Option<f64>,
+    opponent_noise: Option<f64>,
+    noise_schedule: Option<NoiseSchedule>,
+    perception_noise: Option<f64>,
+    initial_populations: Vec<u32>,
+    payoff_matrix: PayoffMatrix,
+    continuation: Option<f64>,
+    moran: Option<MoranConfig>,
+    seed: Option<u64>
+) -> Result<MoranRun, EngineError> {
+    let config = MatchConfig {
+        rounds,
+        noise,
+        player_noise,
+        opponent_noise,
+        noise_schedule: noise_schedule.unwrap_or_default(),
+        perception_noise: perception_noise.unwrap_or(0.0),
+        payoff_matrix,
+        continuation,
+        ..MatchConfig::default()
+    };
+    moran::run_moran(config, moran.unwrap_or_default(), initial_populations, &mut seeded_rng(seed))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn estimate_fixation(
+    resident: String,
+    mutant: String,
+    population_size: u32,
+    repetitions: u32,
+    rounds: u32,
+    noise: f64,
+    player_noise: Option<f64>,
+    opponent_noise: Option<f64>,
+    noise_schedule: Option<NoiseSchedule>,
+    perception_noise: Option<f64>,
+    payoff_matrix: PayoffMatrix,
+    continuation: Option<f64>,
+    moran: Option<MoranConfig>,
+    seed: Option<u64>
+) -> Result<FixationEstimate, EngineError> {
+    let config = MatchConfig {
+        rounds,
+        noise,
+        player_noise,
+        opponent_noise,
+        noise_schedule: noise_schedule.unwrap_or_default(),
+        perception_noise: perception_noise.unwrap_or(0.0),
+        payoff_matrix,
+        continuation,
+        ..MatchConfig::default()
+    };
+    moran::estimate_fixation(
+        config,
+        moran.unwrap_or_default(),
+        &resident,
+        &mutant,
+        population_size,
+        repetitions,
+        &mut seeded_rng(seed)
+    )
+}
+
+#[tauri::command]
+fn init_spatial_grid(
+    width: usize,
+    height: usize,
+    seed: Option<u64>,
+    state: tauri::State<'_, GameState> // 获取 Tauri 注入的全局状态
+) -> Result<Vec<u8>, EngineError> {
+    check_grid_size(width, height)?;
+    let mut rng = seeded_rng(seed);
+    let new_grid = SpatialGrid::new_coin_flip(width, height, &mut rng);
+
+    let bytes = new_grid.to_byte_array();
+
+    // 加锁，并将新生成的宇宙覆盖写入全局内存
+    let mut grid_lock = state.spatial_grid.lock().map_err(poisoned)?;
+    *grid_lock = new_grid;
+    *state.spatial_rng.lock().map_err(poisoned)? = rng;
+
+    Ok(bytes)
+}
+
+#[tauri::command]
+fn step_spatial_grid(
+    payoff_matrix: PayoffMatrix,
+    noise: f64,
+    state: tauri::State<'_, GameState>
+) -> Result<Vec<u8>, EngineError> {
+    check_probability("noise", noise)?;
+    payoff_matrix.validate()?;
+    let mut grid = state.spatial_grid.lock().map_err(poisoned)?;
+    let mut rng = state.spatial_rng.lock().map_err(poisoned)?;
+
+    if grid.width == 0 {
+        return Err(EngineError::GridNotInitialized);
+    }
+
+    grid.step(&payoff_matrix, noise, &mut rng);
+
+    Ok(grid.to_byte_array())
+}
+
+#[tauri::command]
+fn paint_spatial_grid(
+    x: usize,
+    y: usize,
+    strategy_val: u8,
+    brush_size: usize,
+    state: tauri::State<'_, GameState>
+) -> Result<Vec<u8>, EngineError> {
+    let mut grid = state.spatial_grid.lock().map_err(poisoned)?;
+
+    if grid.width == 0 {
+        return Err(EngineError::GridNotInitialized);
+    }
+
+    let new_strat = if strategy_val == 1 {
+        SpatialStrategy::Cooperate
+    } else {
+        SpatialStrategy::Defect
+    };
+
+    let half_brush = brush_size / 2;
+    let start_x = x.saturating_sub(half_brush);
+    let start_y = y.saturating_sub(half_brush);
+    let end_x = (x + half_brush).min(grid.width.saturating_sub(1));
+    let end_y = (y + half_brush).min(grid.height.saturating_sub(1));
+
+    for i in start_x..=end_x {
+        for j in start_y..=end_y {
+            grid.set(i, j, new_strat.clone());
+        }
+    }
+
+    Ok(grid.to_byte_array())
+}
+
+#[tauri::command]
+fn greet_engine() -> String {
+    "Core Engine: v0.4.0 (Custom Payoff Ready)".to_string()
+}
+
+pub fn run() {
+    tauri::Builder
+        ::default()
+        .manage(GameState {
+            spatial_grid: Mutex::new(SpatialGrid::new(0, 0, SpatialStrategy::Cooperate)),
+            spatial_rng: Mutex::new(seeded_rng(None)),
+        })
+        .invoke_handler(
+            tauri::generate_handler![
+                greet_engine,
+                list_strategies,
+                check_payoff_matrix,
+                zero_determinant,
+                parse_machine,
+                run_game,
+                exact_game,
+                run_tournament,
+                run_evolution,
+                run_moran,
+                estimate_fixation,
+                init_spatial_grid,
+                step_spatial_grid,
+                paint_spatial_grid
+            ]
+        )
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
@@ -0,0 +1,401 @@
+use serde::{ Deserialize, Serialize };
+use rand::prelude::*;
+
+use crate::{ calculate_payoff, Action, History, PayoffMatrix, Round, Strategy };
+
+/// How the execution noise rate changes over a match. Rounds count from 0, and each player's
+/// base rate is the one it would have without a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoiseSchedule {
+    #[default]
+    Constant,
+    /// Linear from the base rate at round `start` to `to` at round `end`, then held at `to`.
+    Ramp {
+        start: u32,
+        end: u32,
+        to: f64,
+    },
+    /// `rate` for `length` rounds from round `start`, the base rate otherwise; with `every` set,
+    /// the burst recurs every that many rounds.
+    Shock {
+        start: u32,
+        length: u32,
+        rate: f64,
+        every: Option<u32>,
+    },
+}
+
+impl NoiseSchedule {
+    /// The rate in `round` for a player whose base rate is `base`.
+    pub fn rate(&self, base: f64, round: u32) -> f64 {
+        match *self {
+            NoiseSchedule::Constant => base,
+            NoiseSchedule::Ramp { start, end, to } => {
+                if round <= start {
+                    base
+                } else if round >= end {
+                    to
+                } else {
+                    base + ((to - base) * ((round - start) as f64)) / ((end - start) as f64)
+                }
+            }
+            NoiseSchedule::Shock { start, length, rate, every } => {
+                let Some(since) = round.checked_sub(start) else {
+                    return base;
+                };
+                let phase = every.map_or(since, |every| since % every);
+                if phase < length { rate } else { base }
+            }
+        }
+    }
+}
+
+/// Parameters shared by every match, whichever game mode started it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MatchConfig {
+    /// Fixed match length, or the hard cap on it when `continuation` is set.
+    pub rounds: u32,
+    /// Execution noise: a move is flipped before it is played, so both players see the flip.
+    pub noise: f64,
+    /// Per-player execution noise, overriding `noise` for that seat.
+    #[serde(default)]
+    pub player_noise: Option<f64>,
+    #[serde(default)]
+    pub opponent_noise: Option<f64>,
+    /// Varies both players' execution noise over the match.
+    #[serde(default)]
+    pub noise_schedule: NoiseSchedule,
+    /// Perception noise: each player misreads the opponent's move in its own history with this
+    /// probability, while payoffs still use the moves actually played.
+    #[serde(default)]
+    pub perception_noise: f64,
+    pub payoff_matrix: PayoffMatrix,
+    /// Probability w that another round follows each round (Axelrod's shadow of the future).
+    /// Match length is then geometric with mean 1 / (1 - w), so nobody knows when the game ends.
+    pub continuation: Option<f64>,
+    /// Discount factor applied to round t's payoff as discount^t, reported next to the raw totals.
+    pub discount: Option<f64>,
+}
+
+/// A noiseless 200-round match of the standard Prisoner's Dilemma (T=5, R=3, P=1, S=0).
+impl Default for MatchConfig {
+    fn default() -> Self {
+        MatchConfig {
+            rounds: 200,
+            noise: 0.0,
+            player_noise: None,
+            opponent_noise: None,
+            noise_schedule: NoiseSchedule::Constant,
+            perception_noise: 0.0,
+            payoff_matrix: PayoffMatrix { t: 5, r: 3, p: 1, s: 0 },
+            continuation: None,
+            discount: None,
+        }
+    }
+}
+
+impl MatchConfig {
+    /// Execution noise rates of (player, opponent) in `round`, counting from 0.
+    pub fn noise_at(&self, round: u32) -> (f64, f64) {
+        let rate = |base: Option<f64>| self.noise_schedule.rate(base.unwrap_or(self.noise), round);
+        (rate(self.player_noise), rate(self.opponent_noise))
+    }
+
+    /// True when no move can be flipped or misread in any round.
+    pub fn is_noiseless(&self) -> bool {
+        let peak = match self.noise_schedule {
+            NoiseSchedule::Constant => 0.0,
+            NoiseSchedule::Ramp { to, .. } => to,
+            NoiseSchedule::Shock { rate, .. } => rate,
+        };
+        let bases = [self.player_noise.unwrap_or(self.noise), self.opponent_noise.unwrap_or(self.noise)];
+        peak == 0.0 && bases.iter().all(|&b| b == 0.0) && self.perception_noise == 0.0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub player_name: String,
+    pub opponent_name: String,
+    /// The moves actually played.
+    pub rounds: Vec<Round>,
+    /// What each player believes happened, from its own side (my move, opponent move);
+    /// present only under perception noise, when these diverge from `rounds`.
+    pub player_view: Option<Vec<Round>>,
+    pub opponent_view: Option<Vec<Round>>,
+    pub player_score: i64,
+    pub opponent_score: i64,
+    pub player_discounted_score: Option<f64>,
+    pub opponent_discounted_score: Option<f64>,
+}
+
+/// Seeded runs are bit-identical; without a seed the stream is drawn from the OS.
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => rand::make_rng(),
+    }
+}
+
+/// Stream number `stream` of a run whose streams were split off `base`. Parallel code gives every
+/// unit of work its own stream, so results do not depend on which thread ran what.
+pub fn stream_rng(base: u64, stream: u64) -> StdRng {
+    StdRng::seed_from_u64(base ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+/// Plays one iterated match between p1 and p2, resetting both first.
+/// Both players see the history from their own side, (my move, opponent move), without copying it.
+/// Under perception noise each player instead reads its own history, in which the opponent's
+/// moves may be misperceived.
+/// Every random draw (noise and stochastic strategies) comes from `rng`.
+/// `config` is assumed valid (see `MatchConfig::validate`); noise outside [0, 1] panics.
+pub fn play_match(
+    p1: &mut dyn Strategy,
+    p2: &mut dyn Strategy,
+    config: MatchConfig,
+    rng: &mut StdRng
+) -> MatchResult {
+    let matrix = &config.payoff_matrix;
+    let mut history: Vec<Round> = Vec::with_capacity(config.rounds as usize);
+    let misperceive = config.perception_noise > 0.0;
+    let mut p1_view: Vec<Round> = Vec::new();
+    let mut p2_view: Vec<Round> = Vec::new();
+    let mut p1_score: i64 = 0;
+    let mut p2_score: i64 = 0;
+    let mut p1_discounted = 0.0;
+    let mut p2_discounted = 0.0;
+    let mut weight = 1.0;
+    p1.reset();
+    p2.reset();
+
+    for round in 0..config.rounds {
+        let (h1, h2) = if misperceive {
+            (History::new(&p1_view), History::new(&p2_view))
+        } else {
+            (History::new(&history), History::mirrored(&history))
+        };
+        let mut a1 = p1.next_move(h1, matrix, rng);
+        let mut a2 = p2.next_move(h2, matrix, rng);
+
+        // trembling hand: the flipped move is the one actually played and seen
+        let (noise1, noise2) = config.noise_at(round);
+        if rng.random_bool(noise1) {
+            a1 = a1.toggle();
+        }
+        if rng.random_bool(noise2) {
+            a2 = a2.toggle();
+        }
+
+        history.push((a1, a2));
+        if misperceive {
+            let seen = |a: Action, rng: &mut StdRng| {
+                if rng.random_bool(config.perception_noise) { a.toggle() } else { a }
+            };
+            p1_view.push((a1, seen(a2, rng)));
+            p2_view.push((a2, seen(a1, rng)));
+        }
+        let (s1, s2) = calculate_payoff(a1, a2, matrix);
+        p1_score += s1 as i64;
+        p2_score += s2 as i64;
+
+        if let Some(discount) = config.discount {
+            p1_discounted += weight * (s1 as f64);
+            p2_discounted += weight * (s2 as f64);
+            weight *= discount;
+        }
+        if let Some(w) = config.continuation {
+            if !rng.random_bool(w) {
+                break;
+            }
+        }
+    }
+
+    MatchResult {
+        player_name: p1.name(),
+        opponent_name: p2.name(),
+        rounds: history,
+        player_view: misperceive.then_some(p1_view),
+        opponent_view: misperceive.then_some(p2_view),
+        player_score: p1_score,
+        opponent_score: p2_score,
+        player_discounted_score: config.discount.map(|_| p1_discounted),
+        opponent_discounted_score: config.discount.map(|_| p2_discounted),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ create_strategy, test_config };
+
+    fn rng() -> StdRng {
+        seeded_rng(Some(42))
+    }
+
+    #[test]
+    fn test_tft_vs_always_defect() {
+        let mut tft = create_strategy("tit_for_tat").unwrap();
+        let mut alld = create_strategy("always_defect").unwrap();
+
+        let result = play_match(tft.as_mut(), alld.as_mut(), test_config(10), &mut rng());
+
+        // sucker once, then mutual punishment for the remaining 9 rounds
+        assert_eq!(result.player_score, 9);
+        assert_eq!(result.opponent_score, 5 + 9);
+        assert_eq!(result.rounds[0], (Action::Cooperate, Action::Defect));
+        assert_eq!(result.rounds.len(), 10);
+    }
+
+    #[test]
+    fn test_opponent_sees_mirrored_history() {
+        // Grim Trigger as player 2 must read Always Defect's moves as the *opponent's* moves
+        let mut alld = create_strategy("always_defect").unwrap();
+        let mut grim = create_strategy("grim_trigger").unwrap();
+
+        let result = play_match(alld.as_mut(), grim.as_mut(), test_config(3), &mut rng());
+
+        assert_eq!(
+            result.rounds,
+            vec![
+                (Action::Defect, Action::Cooperate),
+                (Action::Defect, Action::Defect),
+                (Action::Defect, Action::Defect)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_full_noise_flips_every_move() {
+        let mut p1 = create_strategy("always_cooperate").unwrap();
+        let mut p2 = create_strategy("always_cooperate").unwrap();
+        let mut cfg = test_config(5);
+        cfg.noise = 1.0;
+
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+
+        assert!(result.rounds.iter().all(|r| *r == (Action::Defect, Action::Defect)));
+        assert_eq!(result.player_score, 5);
+    }
+
+    #[test]
+    fn test_per_player_noise_overrides_the_shared_rate() {
+        let mut p1 = create_strategy("always_cooperate").unwrap();
+        let mut p2 = create_strategy("always_cooperate").unwrap();
+        let mut cfg = test_config(5);
+        cfg.noise = 1.0;
+        cfg.player_noise = Some(0.0);
+
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+
+        assert!(result.rounds.iter().all(|r| *r == (Action::Cooperate, Action::Defect)));
+    }
+
+    #[test]
+    fn test_noise_schedules() {
+        let ramp = NoiseSchedule::Ramp { start: 10, end: 20, to: 0.5 };
+        assert_eq!(ramp.rate(0.1, 0), 0.1);
+        assert_eq!(ramp.rate(0.1, 10), 0.1);
+        assert!((ramp.rate(0.1, 15) - 0.3).abs() < 1e-12);
+        assert_eq!(ramp.rate(0.1, 99), 0.5);
+
+        let shock = NoiseSchedule::Shock { start: 5, length: 2, rate: 1.0, every: Some(10) };
+        let bursts: Vec<u32> = (0..30).filter(|&r| shock.rate(0.0, r) == 1.0).collect();
+        assert_eq!(bursts, [5, 6, 15, 16, 25, 26]);
+
+        // one burst on two otherwise perfect cooperators
+        let mut p1 = create_strategy("always_cooperate").unwrap();
+        let mut p2 = create_strategy("always_cooperate").unwrap();
+        let mut cfg = test_config(6);
+        cfg.noise_schedule = NoiseSchedule::Shock { start: 2, length: 1, rate: 1.0, every: None };
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+        assert_eq!(result.rounds[2], (Action::Defect, Action::Defect));
+        assert_eq!(result.player_score, 5 * 3 + 1);
+        assert!(!cfg.is_noiseless());
+    }
+
+    #[test]
+    fn test_misperception_diverges_histories_but_not_payoffs() {
+        let mut p1 = create_strategy("tit_for_tat").unwrap();
+        let mut p2 = create_strategy("tit_for_tat").unwrap();
+        let mut cfg = test_config(3);
+        cfg.perception_noise = 1.0;
+
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+
+        // each sees the other's cooperation as defection and retaliates; both really defected in
+        // round 2, which each then misreads as cooperation
+        let (c, d) = (Action::Cooperate, Action::Defect);
+        assert_eq!(result.rounds, vec![(c, c), (d, d), (c, c)]);
+        assert_eq!(result.player_view, Some(vec![(c, d), (d, c), (c, d)]));
+        assert_eq!(result.opponent_view, result.player_view);
+        assert_eq!(result.player_score, 3 + 1 + 3);
+
+        cfg.perception_noise = 0.0;
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+        assert!(result.player_view.is_none());
+    }
+
+    #[test]
+    fn test_strategy_state_resets_between_matches() {
+        let mut grim = create_strategy("grim_trigger").unwrap();
+        let mut alld = create_strategy("always_defect").unwrap();
+        let mut allc = create_strategy("always_cooperate").unwrap();
+
+        play_match(grim.as_mut(), alld.as_mut(), test_config(5), &mut rng());
+        let result = play_match(grim.as_mut(), allc.as_mut(), test_config(5), &mut rng());
+
+        assert!(
+            result.rounds.iter().all(|r| r.0 == Action::Cooperate),
+            "Grim Trigger carried its grudge into a fresh match!"
+        );
+    }
+
+    #[test]
+    fn test_same_seed_replays_identically() {
+        let mut cfg = test_config(200);
+        cfg.noise = 0.05;
+        let run = |seed: u64| {
+            let mut joss = create_strategy("joss").unwrap();
+            let mut random = create_strategy("random").unwrap();
+            play_match(joss.as_mut(), random.as_mut(), cfg, &mut seeded_rng(Some(seed))).rounds
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn test_continuation_probability_ends_match_early() {
+        let mut p1 = create_strategy("tit_for_tat").unwrap();
+        let mut p2 = create_strategy("tit_for_tat").unwrap();
+        let mut cfg = test_config(1000);
+
+        cfg.continuation = Some(0.0);
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+        assert_eq!(result.rounds.len(), 1);
+
+        // w = 0.9 gives a mean length of 10 rounds; the cap is never the binding constraint
+        cfg.continuation = Some(0.9);
+        let mut rng = rng();
+        let total: usize = (0..2000)
+            .map(|_| play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng).rounds.len())
+            .sum();
+        let mean = (total as f64) / 2000.0;
+        assert!((mean - 10.0).abs() < 1.0, "Mean match length {} is not geometric(0.9)", mean);
+    }
+
+    #[test]
+    fn test_discounted_scores_reported_alongside_raw() {
+        let mut p1 = create_strategy("always_cooperate").unwrap();
+        let mut p2 = create_strategy("always_cooperate").unwrap();
+        let mut cfg = test_config(3);
+        cfg.discount = Some(0.5);
+
+        let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng());
+
+        assert_eq!(result.player_score, 9);
+        assert_eq!(result.player_discounted_score, Some(3.0 + 1.5 + 0.75));
+        assert_eq!(result.opponent_discounted_score, Some(5.25));
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::ser::{ SerializeMap, Serializer };
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    UnknownStrategy {
+        id: String,
+        valid_ids: Vec<String>,
+    },
+    UnknownParameter {
+        strategy: String,
+        param: String,
+        valid_params: Vec<String>,
+    },
+    InvalidStrategySpec {
+        spec: String,
+        reason: String,
+    },
+    /// A numeric input outside the range the engine can run with.
+    InvalidParameter {
+        param: String,
+        value: String,
+        reason: String,
+    },
+    /// A state-machine definition that does not parse or refers to undefined states.
+    InvalidMachine {
+        reason: String,
+    },
+    GridNotInitialized,
+    /// Shared app state could not be locked (a previous command panicked while holding it).
+    StateUnavailable(String),
+}
+
+impl EngineError {
+    /// Stable machine-readable tag, sent to the frontend as `kind`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EngineError::UnknownStrategy { .. } => "unknown_strategy",
+            EngineError::UnknownParameter { .. } => "unknown_parameter",
+            EngineError::InvalidStrategySpec { .. } => "invalid_strategy_spec",
+            EngineError::InvalidParameter { .. } => "invalid_parameter",
+            EngineError::InvalidMachine { .. } => "invalid_machine",
+            EngineError::GridNotInitialized => "grid_not_initialized",
+            EngineError::StateUnavailable(_) => "state_unavailable",
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::UnknownStrategy { id, valid_ids } => {
+                write!(f, "unknown strategy id {:?} (valid ids: {})", id, valid_ids.join(", "))
+            }
+            EngineError::UnknownParameter { strategy, param, valid_params } => {
+                if valid_params.is_empty() {
+                    write!(f, "{} takes no parameters (got {:?})", strategy, param)
+                } else {
+                    write!(
+                        f,
+                        "{} has no parameter {:?} (valid parameters: {})",
+                        strategy,
+                        param,
+                        valid_params.join(", ")
+                    )
+                }
+            }
+            EngineError::InvalidStrategySpec { spec, reason } => {
+                write!(f, "invalid strategy spec {:?}: {}", spec, reason)
+            }
+            EngineError::InvalidParameter { param, value, reason } => {
+                write!(f, "invalid {} {}: {}", param, value, reason)
+            }
+            EngineError::InvalidMachine { reason } => {
+                write!(f, "invalid state machine: {}", reason)
+            }
+            EngineError::GridNotInitialized => {
+                write!(f, "Grid not initialized. Please click INIT SPATIAL GRID first.")
+            }
+            EngineError::StateUnavailable(reason) => {
+                write!(f, "engine state unavailable: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// Errors cross the Tauri boundary as `{ kind, message, ...details }` so the UI can both
+/// print the message and react to the specific kind.
+impl Serialize for EngineError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            EngineError::UnknownStrategy { id, valid_ids } => {
+                map.serialize_entry("id", id)?;
+                map.serialize_entry("valid_ids", valid_ids)?;
+            }
+            EngineError::UnknownParameter { strategy, param, valid_params } => {
+                map.serialize_entry("strategy", strategy)?;
+                map.serialize_entry("param", param)?;
+                map.serialize_entry("valid_params", valid_params)?;
+            }
+            EngineError::InvalidStrategySpec { spec, .. } => {
+                map.serialize_entry("spec", spec)?;
+            }
+            EngineError::InvalidParameter { param, value, .. } => {
+                map.serialize_entry("param", param)?;
+                map.serialize_entry("value", value)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,511 @@
+use serde::{ Deserialize, Serialize };
+use rand::prelude::*;
+
+use crate::payoffs::{ expected_payoffs, expected_payoffs_among };
+use crate::validation::{ check_count, check_population, check_probability, MAX_GENERATIONS, MAX_REPETITIONS };
+use crate::{ check_strategy, create_strategy, field_ids, EngineError, MatchConfig, PayoffMatrix, StrategySpec };
+
+/// Shares below this are treated as extinct, so the replicator field can actually shrink.
+const EXTINCTION_SHARE: f64 = 1e-9;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Generation {
+    pub gen_number: u32,
+    /// Fraction of the population playing each strategy; sums to 1.
+    pub shares: Vec<(String, f64)>,
+}
+
+/// How the population composition changes from one generation to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "cli", value(rename_all = "snake_case"))]
+pub enum Dynamics {
+    /// Discrete replicator equation on the shares of an infinite population: x_i' = x_i f_i / f̄.
+    #[default]
+    Replicator,
+    /// The same number of individuals, split in proportion to each strategy's total fitness.
+    FitnessProportional,
+    /// The single best scorer gains one individual from the single worst.
+    BestWorst,
+    /// The whole population is resampled each generation, every offspring drawing its parent
+    /// in proportion to count × fitness, then possibly mutating.
+    WrightFisher,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvolutionConfig {
+    pub dynamics: Dynamics,
+    /// Probability that an offspring switches to a uniformly random other strategy.
+    /// Only Wright–Fisher has offspring to mutate, so other dynamics require 0.
+    pub mutation_rate: f64,
+    /// Matches averaged into each expected payoff when a pairing involves randomness.
+    pub payoff_repetitions: u32,
+    /// Replay the active pairs every generation instead of computing the payoff matrix once,
+    /// so each generation sees fresh sampling noise.
+    pub resample_each_generation: bool,
+    /// Compute memory-one pairs from their Markov chain instead of sampling them. Not used
+    /// while resampling, whose point is the sampling noise.
+    pub exact: bool,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        EvolutionConfig {
+            dynamics: Dynamics::default(),
+            mutation_rate: 0.0,
+            payoff_repetitions: 1,
+            resample_each_generation: false,
+            exact: false,
+        }
+    }
+}
+
+impl EvolutionConfig {
+    pub fn validate(&self) -> Result<(), EngineError> {
+        check_probability("mutation_rate", self.mutation_rate)?;
+        if self.mutation_rate > 0.0 && self.dynamics != Dynamics::WrightFisher {
+            return Err(EngineError::InvalidParameter {
+                param: "mutation_rate".to_string(),
+                value: self.mutation_rate.to_string(),
+                reason: "mutation is only modelled by wright_fisher dynamics".to_string(),
+            });
+        }
+        check_count("payoff_repetitions", self.payoff_repetitions as u64, MAX_REPETITIONS as u64)
+    }
+}
+
+/// Draws an index with probability proportional to `weights`, uniformly among
+/// positive `fallback` entries when every weight is zero.
+pub(crate) fn pick(weights: &[f64], fallback: &[u32], rng: &mut StdRng) -> usize {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        let counts: Vec<f64> = fallback
+            .iter()
+            .map(|&n| n as f64)
+            .collect();
+        return pick(&counts, &[], rng);
+    }
+    let mut target = rng.random::<f64>() * total;
+    for (i, &w) in weights.iter().enumerate() {
+        if target < w {
+            return i;
+        }
+        target -= w;
+    }
+    weights
+        .iter()
+        .rposition(|&w| w > 0.0)
+        .unwrap_or(0)
+}
+
+/// One count per strategy of the default field, 5 each when none are given.
+pub(crate) fn initial_counts(initial_populations: Vec<u32>, ids: &[&str]) -> Result<Vec<u32>, EngineError> {
+    if initial_populations.is_empty() {
+        return Ok(vec![5; ids.len()]);
+    }
+    if initial_populations.len() != ids.len() {
+        return Err(EngineError::InvalidParameter {
+            param: "initial_populations".to_string(),
+            value: format!("{} counts", initial_populations.len()),
+            reason: format!("expected one count per strategy, in the order {}", ids.join(", ")),
+        });
+    }
+    check_population(&initial_populations)?;
+    Ok(initial_populations)
+}
+
+/// Fitness must stay non-negative for proportional growth, so negative payoffs are shifted up by this.
+pub(crate) fn payoff_baseline(matrix: &PayoffMatrix) -> f64 {
+    [matrix.t, matrix.r, matrix.p, matrix.s, 0]
+        .into_iter()
+        .min()
+        .unwrap_or(0) as f64
+}
+
+/// Fitness in a finite population, where nobody plays themselves.
+pub(crate) fn finite_fitness(counts: &[u32], active: &[usize], payoffs: &[Vec<f64>]) -> Vec<f64> {
+    let total: u32 = counts.iter().sum();
+    let mut fitness = vec![0.0; counts.len()];
+    for &i in active {
+        let mut sum = 0.0;
+        for &j in active {
+            let opponents = if i == j { counts[j] - 1 } else { counts[j] };
+            sum += (opponents as f64) * payoffs[i][j];
+        }
+        fitness[i] = sum / (total.saturating_sub(1).max(1) as f64);
+    }
+    fitness
+}
+
+fn replicator_step(shares: &mut [f64], active: &[usize], payoffs: &[Vec<f64>], baseline: f64) {
+    let fitness: Vec<f64> = active
+        .iter()
+        .map(|&i| {
+            active
+                .iter()
+                .map(|&j| shares[j] * payoffs[i][j])
+                .sum::<f64>() - baseline
+        })
+        .collect();
+    let mean: f64 = active
+        .iter()
+        .zip(&fitness)
+        .map(|(&i, f)| shares[i] * f)
+        .sum();
+    if mean <= 0.0 {
+        return;
+    }
+
+    for (&i, f) in active.iter().zip(&fitness) {
+        shares[i] *= f / mean;
+        if shares[i] < EXTINCTION_SHARE {
+            shares[i] = 0.0;
+        }
+    }
+    let total: f64 = shares.iter().sum();
+    shares.iter_mut().for_each(|x| {
+        *x /= total;
+    });
+}
+
+/// Reallocates the same number of individuals in proportion to count × fitness,
+/// handing out rounding leftovers by largest remainder.
+fn proportional_step(counts: &mut [u32], active: &[usize], fitness: &[f64], baseline: f64) {
+    let total: u32 = counts.iter().sum();
+    let weights: Vec<f64> = counts
+        .iter()
+        .zip(fitness)
+        .map(|(&n, f)| (n as f64) * (f - baseline))
+        .collect();
+    let weight_sum: f64 = active
+        .iter()
+        .map(|&i| weights[i])
+        .sum();
+    if weight_sum <= 0.0 {
+        return;
+    }
+
+    let targets: Vec<f64> = weights
+        .iter()
+        .map(|w| ((total as f64) * w) / weight_sum)
+        .collect();
+    for (n, t) in counts.iter_mut().zip(&targets) {
+        *n = t.floor() as u32;
+    }
+    let remainder = |i: usize| targets[i] - targets[i].floor();
+    let mut by_remainder: Vec<usize> = active.to_vec();
+    by_remainder.sort_by(|&a, &b| remainder(b).total_cmp(&remainder(a)));
+    let assigned: u32 = counts.iter().sum();
+    for &i in by_remainder.iter().take((total - assigned) as usize) {
+        counts[i] += 1;
+    }
+}
+
+fn wright_fisher_step(
+    counts: &mut [u32],
+    fitness: &[f64],
+    baseline: f64,
+    mutation_rate: f64,
+    rng: &mut StdRng
+) {
+    let total: u32 = counts.iter().sum();
+    let weights: Vec<f64> = counts
+        .iter()
+        .zip(fitness)
+        .map(|(&n, f)| (n as f64) * (f - baseline))
+        .collect();
+    let mut offspring = vec![0; counts.len()];
+    for _ in 0..total {
+        let mut child = pick(&weights, counts, rng);
+        if counts.len() > 1 && rng.random_bool(mutation_rate) {
+            // uniform over the other strategies: skip over the parent's own index
+            let other = rng.random_range(0..counts.len() - 1);
+            child = if other >= child { other + 1 } else { other };
+        }
+        offspring[child] += 1;
+    }
+    counts.copy_from_slice(&offspring);
+}
+
+fn best_worst_step(counts: &mut [u32], active: &[usize], fitness: &[f64]) {
+    let mut best_idx = 0;
+    let mut max_score = f64::NEG_INFINITY;
+    let mut worst_idx = 0;
+    let mut min_score = f64::INFINITY;
+
+    for &i in active {
+        if fitness[i] > max_score {
+            max_score = fitness[i];
+            best_idx = i;
+        }
+        if fitness[i] < min_score {
+            min_score = fitness[i];
+            worst_idx = i;
+        }
+    }
+
+    if best_idx != worst_idx {
+        counts[best_idx] += 1;
+        counts[worst_idx] -= 1;
+    }
+}
+
+/// Evolves the built-in field under `evolution.dynamics`, using each strategy's mean per-round payoff as fitness.
+/// `initial_populations` has one count per strategy of the default field, or is empty for 5 each.
+/// The pairwise payoffs are computed once unless `evolution.resample_each_generation` is set.
+pub fn run_evolution(
+    config: MatchConfig,
+    evolution: EvolutionConfig,
+    initial_populations: Vec<u32>,
+    generations: u32,
+    rng: &mut StdRng
+) -> Result<Vec<Generation>, EngineError> {
+    config.validate()?;
+    evolution.validate()?;
+    check_count("generations", generations as u64, MAX_GENERATIONS as u64)?;
+    let all_ids = field_ids();
+    for id in &all_ids {
+        check_strategy(&StrategySpec::new(id), &config.payoff_matrix)?;
+    }
+    let mut counts = initial_counts(initial_populations, &all_ids)?;
+    let total: u32 = counts.iter().sum();
+    let mut shares: Vec<f64> = counts
+        .iter()
+        .map(|&n| (n as f64) / (total as f64))
+        .collect();
+    let names = all_ids
+        .iter()
+        .map(|id| create_strategy(id).map(|s| s.name()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let baseline = payoff_baseline(&config.payoff_matrix);
+    let precomputed = if evolution.resample_each_generation {
+        None
+    } else {
+        Some(expected_payoffs(&all_ids, config, evolution.payoff_repetitions, evolution.exact, rng)?)
+    };
+    let mut history = Vec::new();
+
+    for gen in 1..=generations {
+        let current_shares: Vec<(String, f64)> = names
+            .iter()
+            .cloned()
+            .zip(shares.iter().copied())
+            .collect();
+        history.push(Generation { gen_number: gen, shares: current_shares });
+
+        let active: Vec<usize> = shares
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| x > 0.0)
+            .map(|(i, _)| i)
+            .collect();
+        // with mutation a monomorphic population can still be invaded
+        if active.len() <= 1 && evolution.mutation_rate == 0.0 {
+            break;
+        }
+
+        let resampled;
+        let payoffs = match &precomputed {
+            Some(payoffs) => payoffs,
+            None => {
+                resampled = expected_payoffs_among(
+                    &all_ids,
+                    &active,
+                    config,
+                    evolution.payoff_repetitions,
+                    false,
+                    rng
+                )?;
+                &resampled
+            }
+        };
+        match evolution.dynamics {
+            Dynamics::Replicator => replicator_step(&mut shares, &active, payoffs, baseline),
+            dynamics => {
+                let fitness = finite_fitness(&counts, &active, payoffs);
+                match dynamics {
+                    Dynamics::BestWorst => best_worst_step(&mut counts, &active, &fitness),
+                    Dynamics::WrightFisher => {
+                        wright_fisher_step(&mut counts, &fitness, baseline, evolution.mutation_rate, rng)
+                    }
+                    _ => proportional_step(&mut counts, &active, &fitness, baseline),
+                }
+                shares = counts
+                    .iter()
+                    .map(|&n| (n as f64) / (total as f64))
+                    .collect();
+            }
+        }
+    }
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::MAX_POPULATION;
+    use crate::{ seeded_rng, test_config };
+
+    fn evolution(dynamics: Dynamics) -> EvolutionConfig {
+        EvolutionConfig { dynamics, ..EvolutionConfig::default() }
+    }
+
+    /// Initial counts with only the named strategies present.
+    fn populations(present: &[(&str, u32)]) -> Vec<u32> {
+        field_ids()
+            .iter()
+            .map(|id| {
+                present
+                    .iter()
+                    .find(|(p, _)| p == id)
+                    .map_or(0, |&(_, n)| n)
+            })
+            .collect()
+    }
+
+    fn share(gen: &Generation, name: &str) -> f64 {
+        gen.shares
+            .iter()
+            .find(|(n, _)| n == name)
+            .unwrap().1
+    }
+
+    #[test]
+    fn test_replicator_follows_the_discrete_equation() {
+        let initial = populations(&[("always_defect", 1), ("always_cooperate", 1)]);
+        let history = run_evolution(
+            test_config(10),
+            evolution(Dynamics::Replicator),
+            initial,
+            2,
+            &mut seeded_rng(Some(1))
+        ).unwrap();
+
+        // x = 1/2 each: f(ALLD) = (1 + 5) / 2 = 3, f(ALLC) = (0 + 3) / 2 = 1.5, f̄ = 2.25
+        let alld = share(&history[1], "Always Defect");
+        assert!((alld - (0.5 * 3.0) / 2.25).abs() < 1e-12);
+        assert!((alld + share(&history[1], "Always Cooperate") - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_count_based_dynamics_keep_population_size() {
+        let initial = populations(&[("tit_for_tat", 4), ("always_defect", 3), ("always_cooperate", 3)]);
+        for dynamics in [Dynamics::FitnessProportional, Dynamics::BestWorst, Dynamics::WrightFisher] {
+            let history = run_evolution(
+                test_config(10),
+                evolution(dynamics),
+                initial.clone(),
+                20,
+                &mut seeded_rng(Some(1))
+            ).unwrap();
+            for gen in &history {
+                let total: f64 = gen.shares
+                    .iter()
+                    .map(|(_, x)| x)
+                    .sum();
+                assert!((total - 1.0).abs() < 1e-12);
+                // shares of a ten-individual population are multiples of 0.1
+                for (_, x) in &gen.shares {
+                    assert!(((x * 10.0).round() - x * 10.0).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_worst_moves_one_individual() {
+        let initial = populations(&[("always_defect", 5), ("always_cooperate", 5)]);
+        let history = run_evolution(
+            test_config(10),
+            evolution(Dynamics::BestWorst),
+            initial,
+            2,
+            &mut seeded_rng(Some(1))
+        ).unwrap();
+        assert!((share(&history[1], "Always Defect") - 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_wright_fisher_mutation_revives_extinct_strategies() {
+        let initial = populations(&[("always_defect", 50)]);
+        let frozen = run_evolution(
+            test_config(10),
+            evolution(Dynamics::WrightFisher),
+            initial.clone(),
+            10,
+            &mut seeded_rng(Some(1))
+        ).unwrap();
+        assert_eq!(frozen.len(), 1);
+
+        let mutating = EvolutionConfig { mutation_rate: 0.1, ..evolution(Dynamics::WrightFisher) };
+        let history = run_evolution(test_config(10), mutating, initial, 10, &mut seeded_rng(Some(1))).unwrap();
+        assert_eq!(history.len(), 10);
+        let last = history.last().unwrap();
+        let present = last.shares
+            .iter()
+            .filter(|(_, x)| *x > 0.0)
+            .count();
+        assert!(present > 1);
+    }
+
+    #[test]
+    fn test_population_counts_must_cover_the_field() {
+        let short = vec![5; field_ids().len() - 1];
+        match run_evolution(test_config(10), evolution(Dynamics::Replicator), short, 10, &mut seeded_rng(Some(1))) {
+            Err(EngineError::InvalidParameter { param, .. }) => assert_eq!(param, "initial_populations"),
+            other => panic!("a short population list was accepted: {:?}", other.map(|h| h.len())),
+        }
+        let defaults = run_evolution(test_config(10), evolution(Dynamics::Replicator), vec![], 1, &mut seeded_rng(Some(1)));
+        assert_eq!(defaults.unwrap()[0].shares.len(), field_ids().len());
+    }
+
+    #[test]
+    fn test_population_total_is_capped() {
+        let huge = populations(&[("always_defect", MAX_POPULATION), ("always_cooperate", 1)]);
+        let wright_fisher = evolution(Dynamics::WrightFisher);
+        match run_evolution(test_config(10), wright_fisher, huge, 10, &mut seeded_rng(Some(1))) {
+            Err(EngineError::InvalidParameter { param, .. }) => assert_eq!(param, "initial_populations"),
+            other => panic!("an oversized population was accepted: {:?}", other.map(|h| h.len())),
+        }
+        let largest = populations(&[("always_defect", MAX_POPULATION)]);
+        assert!(run_evolution(test_config(10), wright_fisher, largest, 1, &mut seeded_rng(Some(1))).is_ok());
+    }
+
+    #[test]
+    fn test_mutation_requires_wright_fisher() {
+        let replicator = EvolutionConfig { mutation_rate: 0.1, ..evolution(Dynamics::Replicator) };
+        assert!(run_evolution(test_config(10), replicator, vec![], 10, &mut seeded_rng(Some(1))).is_err());
+    }
+
+    #[test]
+    fn test_resampling_draws_fresh_payoffs_even_when_exact_is_set() {
+        let noisy = MatchConfig { noise: 0.1, ..test_config(10) };
+        let resample = EvolutionConfig { resample_each_generation: true, exact: true, ..evolution(Dynamics::Replicator) };
+        let run = |seed| run_evolution(noisy, resample, vec![], 5, &mut seeded_rng(Some(seed))).unwrap();
+
+        let (a, b) = (run(1), run(2));
+        assert!(a.iter().zip(&b).any(|(x, y)| x.shares != y.shares));
+    }
+
+    #[test]
+    fn test_precomputed_payoffs_match_resampling_for_deterministic_play() {
+        let initial = populations(&[("tit_for_tat", 3), ("always_defect", 3), ("always_cooperate", 3)]);
+        let resample = EvolutionConfig { resample_each_generation: true, ..evolution(Dynamics::Replicator) };
+
+        let once = run_evolution(
+            test_config(10),
+            evolution(Dynamics::Replicator),
+            initial.clone(),
+            30,
+            &mut seeded_rng(Some(1))
+        ).unwrap();
+        let every = run_evolution(test_config(10), resample, initial, 30, &mut seeded_rng(Some(1))).unwrap();
+
+        assert_eq!(once.len(), every.len());
+        for (a, b) in once.iter().zip(&every) {
+            assert_eq!(a.shares, b.shares);
+        }
+    }
+}
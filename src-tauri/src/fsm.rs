@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use rand::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+use crate::{ Action, EngineError, History, PayoffMatrix, Strategy };
+
+/// One state of a machine: the move played while in it, and the state the opponent's
+/// reply to that move leads to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FsmState {
+    pub action: Action,
+    pub on_cooperate: String,
+    pub on_defect: String,
+}
+
+/// A finite-state strategy as written by hand, in JSON or TOML:
+///
+/// ```toml
+/// name = "Tit-For-Tat"
+/// initial = "nice"
+///
+/// [states.nice]
+/// action = "Cooperate"
+/// on_cooperate = "nice"
+/// on_defect = "angry"
+///
+/// [states.angry]
+/// action = "Defect"
+/// on_cooperate = "nice"
+/// on_defect = "angry"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FsmDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub initial: String,
+    pub states: BTreeMap<String, FsmState>,
+}
+
+fn invalid(reason: impl ToString) -> EngineError {
+    EngineError::InvalidMachine { reason: reason.to_string() }
+}
+
+impl FsmDefinition {
+    pub fn from_json(source: &str) -> Result<Self, EngineError> {
+        serde_json::from_str(source).map_err(invalid)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, EngineError> {
+        toml::from_str(source).map_err(invalid)
+    }
+
+    /// JSON if the source is an object, TOML otherwise; checked with `validate`.
+    pub fn parse(source: &str) -> Result<Self, EngineError> {
+        let definition = if source.trim_start().starts_with('{') {
+            Self::from_json(source)?
+        } else {
+            Self::from_toml(source)?
+        };
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Rejects machines that name a state they do not define.
+    pub fn validate(&self) -> Result<(), EngineError> {
+        let check = |target: &str, what: String| {
+            if self.states.contains_key(target) {
+                Ok(())
+            } else {
+                Err(invalid(format!("{}: {:?} names a state that is not defined", what, target)))
+            }
+        };
+        check(&self.initial, format!("{} initial", self.name))?;
+        for (name, state) in &self.states {
+            check(&state.on_cooperate, format!("{} {}.on_cooperate", self.name, name))?;
+            check(&state.on_defect, format!("{} {}.on_defect", self.name, name))?;
+        }
+        Ok(())
+    }
+}
+
+/// A compiled `FsmDefinition`: states become indices, and the current one is tracked
+/// round by round instead of being replayed from the history.
+#[derive(Debug, Clone)]
+pub struct FsmStrategy {
+    name: String,
+    /// Per state: its action and the next state after the opponent cooperates or defects.
+    states: Vec<(Action, [usize; 2])>,
+    initial: usize,
+    current: usize,
+}
+
+impl FsmStrategy {
+    pub fn new(definition: &FsmDefinition) -> Result<Self, EngineError> {
+        definition.validate()?;
+        let index = |name: &String| {
+            definition.states
+                .keys()
+                .position(|k| k == name)
+                .expect("validated")
+        };
+        let states = definition.states
+            .values()
+            .map(|s| (s.action, [index(&s.on_cooperate), index(&s.on_defect)]))
+            .collect();
+        let initial = index(&definition.initial);
+        Ok(FsmStrategy { name: definition.name.clone(), states, initial, current: initial })
+    }
+}
+
+impl Strategy for FsmStrategy {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        _matrix: &PayoffMatrix,
+        _rng: &mut StdRng
+    ) -> Action {
+        match history.last() {
+            None => {
+                self.current = self.initial;
+            }
+            Some((_, theirs)) => {
+                let reply = if theirs == Action::Cooperate { 0 } else { 1 };
+                self.current = self.states[self.current].1[reply];
+            }
+        }
+        self.states[self.current].0
+    }
+    fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Action::{ Cooperate as C, Defect as D };
+    use crate::{ create_strategy, play_match, seeded_rng, test_config, MatchConfig };
+
+    const FORTRESS3: &str = include_str!("../machines/fortress3.toml");
+    const FORTRESS4: &str = include_str!("../machines/fortress4.json");
+
+    fn moves(rounds: &[(Action, Action)]) -> Vec<Action> {
+        rounds
+            .iter()
+            .map(|r| r.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_tit_for_tat_machine_plays_like_tit_for_tat() {
+        let tft = FsmDefinition::parse(
+            r#"{
+                "name": "Tit-For-Tat",
+                "initial": "nice",
+                "states": {
+                    "nice": { "action": "Cooperate", "on_cooperate": "nice", "on_defect": "angry" },
+                    "angry": { "action": "Defect", "on_cooperate": "nice", "on_defect": "angry" }
+                }
+            }"#
+        ).unwrap();
+        let mut machine = FsmStrategy::new(&tft).unwrap();
+        let mut builtin = create_strategy("tit_for_tat").unwrap();
+        let mut opponent = create_strategy("random").unwrap();
+        let noisy = MatchConfig { noise: 0.1, ..test_config(30) };
+
+        let a = play_match(&mut machine, opponent.as_mut(), noisy, &mut seeded_rng(Some(3)));
+        let b = play_match(builtin.as_mut(), opponent.as_mut(), noisy, &mut seeded_rng(Some(3)));
+        assert_eq!(a.rounds, b.rounds);
+    }
+
+    #[test]
+    fn test_fortresses_let_in_only_those_who_know_the_handshake() {
+        for (source, handshake) in [(FORTRESS3, 2), (FORTRESS4, 3)] {
+            let definition = FsmDefinition::parse(source).unwrap();
+            let mut fortress = FsmStrategy::new(&definition).unwrap();
+            let mut twin = FsmStrategy::new(&definition).unwrap();
+
+            let mirror = play_match(&mut fortress, &mut twin, test_config(30), &mut seeded_rng(Some(1)));
+            let opened: Vec<Action> = std::iter
+                ::repeat_n(D, handshake)
+                .chain(std::iter::repeat_n(C, 30 - handshake))
+                .collect();
+            assert_eq!(moves(&mirror.rounds), opened, "{}", definition.name);
+
+            let mut cooperator = create_strategy("always_cooperate").unwrap();
+            let exploited = play_match(&mut fortress, cooperator.as_mut(), test_config(30), &mut seeded_rng(Some(1)));
+            assert!(moves(&exploited.rounds).iter().all(|&a| a == D));
+        }
+    }
+
+    #[test]
+    fn test_undefined_states_and_malformed_sources_are_rejected() {
+        let mut broken = FsmDefinition::parse(FORTRESS3).unwrap();
+        broken.states.get_mut("guard").unwrap().on_defect = "gate".to_string();
+        assert!(matches!(FsmStrategy::new(&broken), Err(EngineError::InvalidMachine { .. })));
+
+        broken.initial = "moat".to_string();
+        assert!(broken.validate().unwrap_err().to_string().contains("moat"));
+
+        assert!(FsmDefinition::parse("{ \"name\": \"Nothing\" }").is_err());
+        assert!(FsmDefinition::parse("name = ").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::Round;
+
+/// A borrowed, allocation-free view of a match history from one player's side.
+/// Every round reads as (my move, opponent move); the opponent's view of the same
+/// slice just swaps the pair on access instead of copying the whole history.
+#[derive(Debug, Clone, Copy)]
+pub struct History<'a> {
+    rounds: &'a [Round],
+    swapped: bool,
+}
+
+impl<'a> History<'a> {
+    /// View of a history that is already stored from this player's side.
+    pub fn new(rounds: &'a [Round]) -> Self {
+        Self { rounds, swapped: false }
+    }
+
+    /// View of a history stored from the opponent's side.
+    pub fn mirrored(rounds: &'a [Round]) -> Self {
+        Self { rounds, swapped: true }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+
+    #[inline]
+    fn orient(&self, (a, b): Round) -> Round {
+        if self.swapped { (b, a) } else { (a, b) }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Round> {
+        self.rounds.get(index).map(|&r| self.orient(r))
+    }
+
+    pub fn last(&self) -> Option<Round> {
+        self.rounds.last().map(|&r| self.orient(r))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Round> + ExactSizeIterator + 'a {
+        let swapped = self.swapped;
+        self.rounds.iter().map(move |&(a, b)| if swapped { (b, a) } else { (a, b) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Action::{ Cooperate as C, Defect as D };
+
+    #[test]
+    fn test_mirrored_view_swaps_without_copying() {
+        let rounds = vec![(C, D), (D, D), (D, C)];
+
+        let mine = History::new(&rounds);
+        let theirs = History::mirrored(&rounds);
+
+        assert_eq!(mine.last(), Some((D, C)));
+        assert_eq!(theirs.last(), Some((C, D)));
+        assert_eq!(theirs.get(0), Some((D, C)));
+        assert_eq!(theirs.iter().collect::<Vec<_>>(), vec![(D, C), (D, D), (C, D)]);
+        assert_eq!(theirs.len(), 3);
+        assert!(History::new(&[]).last().is_none());
+    }
+}
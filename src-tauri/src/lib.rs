@@ -1,13 +1,51 @@
-use std::sync::Mutex;
 use serde::{ Deserialize, Serialize };
 use rand::prelude::*;
 
+#[cfg(feature = "gui")]
+mod commands;
+pub mod engine;
+pub mod error;
+pub mod evolution;
+pub mod fsm;
+pub mod history;
+pub mod markov;
+pub mod moran;
+pub mod payoffs;
+pub mod registry;
 pub mod spatial;
-use spatial::{ SpatialGrid, Strategy as SpatialStrategy };
-
-pub struct GameState {
-    pub spatial_grid: Mutex<SpatialGrid<SpatialStrategy>>,
-}
+pub mod tournament;
+pub mod validation;
+pub mod zero_determinant;
+pub use history::History;
+pub use engine::{ play_match, seeded_rng, stream_rng, MatchConfig, MatchResult, NoiseSchedule };
+pub use error::EngineError;
+pub use evolution::{ run_evolution, Dynamics, EvolutionConfig, Generation };
+pub use fsm::{ FsmDefinition, FsmState, FsmStrategy };
+pub use markov::{ exact_game, exact_match, ExactMatch };
+pub use moran::{ estimate_fixation, run_moran, FixationEstimate, MoranConfig, MoranRun };
+pub use payoffs::expected_payoffs;
+pub use registry::{
+    build_strategy,
+    check_strategy,
+    create_strategy,
+    field_ids,
+    list_strategies,
+    memory_one_of,
+    strategy_ids,
+    StrategyInfo,
+    StrategySpec,
+};
+pub use tournament::{
+    run_tournament,
+    CrossTable,
+    Outcomes,
+    Pairing,
+    StrategyStats,
+    TournamentConfig,
+    TournamentResult,
+};
+pub use validation::PayoffWarning;
+pub use zero_determinant::{ ZeroDeterminant, ZeroDeterminantStrategy };
 
 // --- 1. Basic Data Structures ---
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -27,15 +65,6 @@ impl Action {
 
 pub type Round = (Action, Action);
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MatchResult {
-    pub player_name: String,
-    pub opponent_name: String,
-    pub rounds: Vec<Round>,
-    pub player_score: i32,
-    pub opponent_score: i32,
-}
-
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PayoffMatrix {
     pub t: i32, // Temptation
@@ -45,9 +74,18 @@ pub struct PayoffMatrix {
 }
 
 // --- 2. Strategy Trait ---
+/// A strategy instance lives for one match at a time and may keep private state between moves.
+/// The engine calls `reset` before every match, so an instance can be reused across matches.
+/// Stochastic strategies must draw from the supplied `rng` so that seeded runs are reproducible.
 pub trait Strategy: Send + Sync {
     fn name(&self) -> String;
-    fn next_move(&self, history: &[Round], matrix: &PayoffMatrix) -> Action;
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        matrix: &PayoffMatrix,
+        rng: &mut StdRng
+    ) -> Action;
+    fn reset(&mut self) {}
 }
 
 // --- 3. Strategy Implementations ---
@@ -56,9 +94,14 @@ impl Strategy for TitForTat {
     fn name(&self) -> String {
         "Tit-For-Tat".to_string()
     }
-    fn next_move(&self, history: &[Round], _matrix: &PayoffMatrix) -> Action {
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        _matrix: &PayoffMatrix,
+        _rng: &mut StdRng
+    ) -> Action {
         match history.last() {
-            Some(&(_, opponent_last_move)) => opponent_last_move,
+            Some((_, opponent_last_move)) => opponent_last_move,
             None => Action::Cooperate,
         }
     }
@@ -69,24 +112,42 @@ impl Strategy for AlwaysDefect {
     fn name(&self) -> String {
         "Always Defect".to_string()
     }
-    fn next_move(&self, _history: &[Round], _matrix: &PayoffMatrix) -> Action {
+    fn next_move(
+        &mut self,
+        _history: History<'_>,
+        _matrix: &PayoffMatrix,
+        _rng: &mut StdRng
+    ) -> Action {
         Action::Defect
     }
 }
 
-pub struct GrimTrigger;
+#[derive(Default)]
+pub struct GrimTrigger {
+    triggered: bool,
+}
 impl Strategy for GrimTrigger {
     fn name(&self) -> String {
         "Grim Trigger".to_string()
     }
-    fn next_move(&self, history: &[Round], _matrix: &PayoffMatrix) -> Action {
-        let has_opponent_cheated = history.iter().any(|(_, opp)| *opp == Action::Defect);
-        if has_opponent_cheated {
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        _matrix: &PayoffMatrix,
+        _rng: &mut StdRng
+    ) -> Action {
+        if let Some((_, Action::Defect)) = history.last() {
+            self.triggered = true;
+        }
+        if self.triggered {
             Action::Defect
         } else {
             Action::Cooperate
         }
     }
+    fn reset(&mut self) {
+        self.triggered = false;
+    }
 }
 
 pub struct AlwaysCooperate;
@@ -94,18 +155,34 @@ impl Strategy for AlwaysCooperate {
     fn name(&self) -> String {
         "Always Cooperate".to_string()
     }
-    fn next_move(&self, _history: &[Round], _matrix: &PayoffMatrix) -> Action {
+    fn next_move(
+        &mut self,
+        _history: History<'_>,
+        _matrix: &PayoffMatrix,
+        _rng: &mut StdRng
+    ) -> Action {
         Action::Cooperate
     }
 }
 
-pub struct Random;
+pub struct Random {
+    pub cooperation_probability: f64,
+}
 impl Strategy for Random {
     fn name(&self) -> String {
         "Random".to_string()
     }
-    fn next_move(&self, _history: &[Round], _matrix: &PayoffMatrix) -> Action {
-        if rand::rng().random_bool(0.5) { Action::Cooperate } else { Action::Defect }
+    fn next_move(
+        &mut self,
+        _history: History<'_>,
+        _matrix: &PayoffMatrix,
+        rng: &mut StdRng
+    ) -> Action {
+        if rng.random_bool(self.cooperation_probability) {
+            Action::Cooperate
+        } else {
+            Action::Defect
+        }
     }
 }
 
@@ -114,10 +191,15 @@ impl Strategy for Pavlov {
     fn name(&self) -> String {
         "Pavlov".to_string()
     }
-    fn next_move(&self, history: &[Round], matrix: &PayoffMatrix) -> Action {
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        matrix: &PayoffMatrix,
+        _rng: &mut StdRng
+    ) -> Action {
         match history.last() {
             None => Action::Cooperate,
-            Some(&(my_last, opp_last)) => {
+            Some((my_last, opp_last)) => {
                 let (my_score, _) = calculate_payoff(my_last, opp_last, matrix);
                 if my_score >= matrix.r {
                     my_last
@@ -129,19 +211,26 @@ impl Strategy for Pavlov {
     }
 }
 
-pub struct GenerousTFT;
+pub struct GenerousTFT {
+    pub forgiveness: f64,
+}
 impl Strategy for GenerousTFT {
     fn name(&self) -> String {
         "Generous TFT".to_string()
     }
-    fn next_move(&self, history: &[Round], _matrix: &PayoffMatrix) -> Action {
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        _matrix: &PayoffMatrix,
+        rng: &mut StdRng
+    ) -> Action {
         match history.last() {
             None => Action::Cooperate,
-            Some(&(_, opp_last)) => {
+            Some((_, opp_last)) => {
                 match opp_last {
                     Action::Cooperate => Action::Cooperate,
                     Action::Defect => {
-                        if rand::rng().random_bool(0.1) {
+                        if rng.random_bool(self.forgiveness) {
                             Action::Cooperate
                         } else {
                             Action::Defect
@@ -153,379 +242,103 @@ impl Strategy for GenerousTFT {
     }
 }
 
-pub struct Joss;
+pub struct Joss {
+    pub sneakiness: f64,
+}
 impl Strategy for Joss {
     fn name(&self) -> String {
         "Joss".to_string()
     }
-    fn next_move(&self, history: &[Round], _matrix: &PayoffMatrix) -> Action {
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        _matrix: &PayoffMatrix,
+        rng: &mut StdRng
+    ) -> Action {
         match history.last() {
             None => Action::Cooperate,
-            Some(&(_, opp_last)) => {
+            Some((_, opp_last)) => {
                 if opp_last == Action::Defect {
                     Action::Defect
                 } else {
-                    if rand::rng().random_bool(0.1) { Action::Defect } else { Action::Cooperate }
+                    if rng.random_bool(self.sneakiness) { Action::Defect } else { Action::Cooperate }
                 }
             }
         }
     }
 }
 
-// --- 4. Strategy Factory ---
-pub fn create_strategy(id: &str) -> Box<dyn Strategy> {
-    match id {
-        "tit_for_tat" => Box::new(TitForTat),
-        "always_defect" => Box::new(AlwaysDefect),
-        "grim_trigger" => Box::new(GrimTrigger),
-        "always_cooperate" => Box::new(AlwaysCooperate),
-        "random" => Box::new(Random),
-        "pavlov" => Box::new(Pavlov),
-        "generous_tft" => Box::new(GenerousTFT),
-        "joss" => Box::new(Joss),
-        _ => Box::new(AlwaysDefect),
-    }
-}
-
-// --- 5. Tauri Commands ---
-pub fn calculate_payoff(a1: Action, a2: Action, matrix: &PayoffMatrix) -> (i32, i32) {
-    match (a1, a2) {
-        (Action::Defect, Action::Cooperate) => (matrix.t, matrix.s),
-        (Action::Cooperate, Action::Cooperate) => (matrix.r, matrix.r),
-        (Action::Defect, Action::Defect) => (matrix.p, matrix.p),
-        (Action::Cooperate, Action::Defect) => (matrix.s, matrix.t),
-    }
+/// Cooperates with a fixed probability for each outcome of the previous round, read from its own
+/// side: p_cc after (C, C), p_cd after (C, D), and so on. Tit-For-Tat is (1, 1, 0, 1, 0),
+/// Pavlov (1, 1, 0, 0, 1), Generous TFT (1, 1, g, 1, g); ALLC, ALLD and Random are constant.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryOne {
+    pub p_first: f64,
+    pub p_cc: f64,
+    pub p_cd: f64,
+    pub p_dc: f64,
+    pub p_dd: f64,
 }
-
-#[tauri::command]
-fn run_game(
-    p1_id: String,
-    p2_id: String,
-    rounds: u32,
-    noise: f64,
-    payoff_matrix: PayoffMatrix
-) -> MatchResult {
-    let p1 = create_strategy(&p1_id);
-    let p2 = create_strategy(&p2_id);
-    let mut history: Vec<Round> = Vec::with_capacity(rounds as usize);
-    let mut p1_score = 0;
-    let mut p2_score = 0;
-    let mut rng = rand::rng();
-
-    for _ in 0..rounds {
-        let mut a1 = p1.next_move(&history, &payoff_matrix);
-        let history_for_p2: Vec<Round> = history
-            .iter()
-            .map(|(my, opp)| (*opp, *my))
-            .collect();
-        let mut a2 = p2.next_move(&history_for_p2, &payoff_matrix);
-
-        if rng.random_bool(noise) {
-            a1 = a1.toggle();
-        }
-        if rng.random_bool(noise) {
-            a2 = a2.toggle();
+impl MemoryOne {
+    /// Chance of cooperating after `last` (my move, opponent move), or on the first move.
+    pub fn cooperation_probability(&self, last: Option<Round>) -> f64 {
+        match last {
+            None => self.p_first,
+            Some((Action::Cooperate, Action::Cooperate)) => self.p_cc,
+            Some((Action::Cooperate, Action::Defect)) => self.p_cd,
+            Some((Action::Defect, Action::Cooperate)) => self.p_dc,
+            Some((Action::Defect, Action::Defect)) => self.p_dd,
         }
-
-        history.push((a1, a2));
-        let (s1, s2) = calculate_payoff(a1, a2, &payoff_matrix);
-        p1_score += s1;
-        p2_score += s2;
-    }
-
-    MatchResult {
-        player_name: p1.name(),
-        opponent_name: p2.name(),
-        rounds: history,
-        player_score: p1_score,
-        opponent_score: p2_score,
     }
 }
-
-#[derive(Debug, Serialize)]
-pub struct TournamentResult {
-    pub ranking: Vec<(String, i32)>,
-}
-
-#[tauri::command]
-fn run_tournament(rounds: u32, noise: f64, payoff_matrix: PayoffMatrix) -> TournamentResult {
-    let all_ids = vec![
-        "tit_for_tat",
-        "always_defect",
-        "grim_trigger",
-        "always_cooperate",
-        "random",
-        "pavlov",
-        "generous_tft",
-        "joss"
-    ];
-    let mut total_scores = vec![0; all_ids.len()];
-
-    for i in 0..all_ids.len() {
-        for j in 0..all_ids.len() {
-            let p1 = create_strategy(all_ids[i]);
-            let p2 = create_strategy(all_ids[j]);
-            let mut history = Vec::with_capacity(rounds as usize);
-            let mut s1_sum = 0;
-            let mut rng = rand::rng();
-
-            for _ in 0..rounds {
-                let mut a1 = p1.next_move(&history, &payoff_matrix);
-                let history_for_p2: Vec<Round> = history
-                    .iter()
-                    .map(|(my, opp)| (*opp, *my))
-                    .collect();
-                let mut a2 = p2.next_move(&history_for_p2, &payoff_matrix);
-
-                if rng.random_bool(noise) {
-                    a1 = a1.toggle();
-                }
-                if rng.random_bool(noise) {
-                    a2 = a2.toggle();
-                }
-
-                history.push((a1, a2));
-                let (s1, _) = calculate_payoff(a1, a2, &payoff_matrix);
-                s1_sum += s1;
-            }
-            total_scores[i] += s1_sum;
-        }
+impl Strategy for MemoryOne {
+    fn name(&self) -> String {
+        "Memory-One".to_string()
     }
-
-    let mut ranking: Vec<(String, i32)> = all_ids
-        .into_iter()
-        .zip(total_scores.into_iter())
-        .map(|(id, score)| (create_strategy(id).name(), score))
-        .collect();
-    ranking.sort_by(|a, b| b.1.cmp(&a.1));
-    TournamentResult { ranking }
-}
-
-#[derive(Debug, Serialize, Clone)]
-pub struct Generation {
-    pub gen_number: u32,
-    pub populations: Vec<(String, u32)>,
-}
-
-#[tauri::command]
-fn run_evolution(
-    rounds: u32,
-    noise: f64,
-    initial_populations: Vec<u32>,
-    generations: u32,
-    payoff_matrix: PayoffMatrix
-) -> Vec<Generation> {
-    let all_ids = vec![
-        "tit_for_tat",
-        "always_defect",
-        "grim_trigger",
-        "always_cooperate",
-        "random",
-        "pavlov",
-        "generous_tft",
-        "joss"
-    ];
-    let mut population = if initial_populations.len() == all_ids.len() {
-        initial_populations
-    } else {
-        vec![5; all_ids.len()]
-    };
-    let mut history = Vec::new();
-    let mut rng = rand::rng();
-
-    for gen in 1..=generations {
-        let current_pop_display: Vec<(String, u32)> = all_ids
-            .iter()
-            .zip(population.iter())
-            .map(|(id, &count)| (create_strategy(id).name(), count))
-            .collect();
-        history.push(Generation { gen_number: gen, populations: current_pop_display });
-
-        let active_strategies: Vec<usize> = population
-            .iter()
-            .enumerate()
-            .filter(|(_, &count)| count > 0)
-            .map(|(i, _)| i)
-            .collect();
-        if active_strategies.len() <= 1 {
-            break;
-        }
-
-        let mut scores = vec![0; all_ids.len()];
-
-        for &i in &active_strategies {
-            for &j in &active_strategies {
-                let p1 = create_strategy(all_ids[i]);
-                let p2 = create_strategy(all_ids[j]);
-                let mut p1_total = 0;
-                let mut history_vec = Vec::with_capacity(rounds as usize);
-
-                for _ in 0..rounds {
-                    let mut a1 = p1.next_move(&history_vec, &payoff_matrix);
-                    let hist_p2: Vec<Round> = history_vec
-                        .iter()
-                        .map(|(m, o)| (*o, *m))
-                        .collect();
-                    let mut a2 = p2.next_move(&hist_p2, &payoff_matrix);
-
-                    if rng.random_bool(noise) {
-                        a1 = a1.toggle();
-                    }
-                    if rng.random_bool(noise) {
-                        a2 = a2.toggle();
-                    }
-
-                    history_vec.push((a1, a2));
-                    let (s1, _) = calculate_payoff(a1, a2, &payoff_matrix);
-                    p1_total += s1;
-                }
-
-                let opponent_count = if i == j { population[j] - 1 } else { population[j] };
-                if opponent_count > 0 {
-                    scores[i] += p1_total * (opponent_count as i32);
-                }
-            }
-        }
-
-        let mut best_idx = 0;
-        let mut max_score = -1;
-        let mut worst_idx = 0;
-        let mut min_score = i32::MAX;
-
-        for &i in &active_strategies {
-            let avg_score = scores[i];
-            if avg_score > max_score {
-                max_score = avg_score;
-                best_idx = i;
-            }
-            if avg_score < min_score {
-                min_score = avg_score;
-                worst_idx = i;
-            }
-        }
-
-        if best_idx != worst_idx {
-            population[best_idx] += 1;
-            population[worst_idx] -= 1;
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        _matrix: &PayoffMatrix,
+        rng: &mut StdRng
+    ) -> Action {
+        if rng.random_bool(self.cooperation_probability(history.last())) {
+            Action::Cooperate
+        } else {
+            Action::Defect
         }
     }
-    history
-}
-
-#[tauri::command]
-fn init_spatial_grid(
-    width: usize,
-    height: usize,
-    state: tauri::State<'_, GameState> // 获取 Tauri 注入的全局状态
-) -> Vec<u8> {
-    let new_grid = SpatialGrid::new_random(width, height, || {
-        if rand::random::<bool>() { SpatialStrategy::Cooperate } else { SpatialStrategy::Defect }
-    });
-
-    let bytes = new_grid.to_byte_array();
-
-    // 加锁，并将新生成的宇宙覆盖写入全局内存
-    let mut grid_lock = state.spatial_grid.lock().unwrap();
-    *grid_lock = new_grid;
-
-    bytes
 }
 
-#[tauri::command]
-fn step_spatial_grid(
-    payoff_matrix: PayoffMatrix,
-    noise: f64,
-    state: tauri::State<'_, GameState>
-) -> Result<Vec<u8>, String> {
-    let mut grid = state.spatial_grid.lock().map_err(|e| e.to_string())?;
-
-    if grid.width == 0 {
-        return Err("Grid not initialized. Please click INIT SPATIAL GRID first.".to_string());
+// --- 4. Payoffs ---
+pub fn calculate_payoff(a1: Action, a2: Action, matrix: &PayoffMatrix) -> (i32, i32) {
+    match (a1, a2) {
+        (Action::Defect, Action::Cooperate) => (matrix.t, matrix.s),
+        (Action::Cooperate, Action::Cooperate) => (matrix.r, matrix.r),
+        (Action::Defect, Action::Defect) => (matrix.p, matrix.p),
+        (Action::Cooperate, Action::Defect) => (matrix.s, matrix.t),
     }
-
-    let play_match = |p1: &SpatialStrategy, p2: &SpatialStrategy| -> f32 {
-        match (p1, p2) {
-            (SpatialStrategy::Cooperate, SpatialStrategy::Cooperate) => payoff_matrix.r as f32,
-            (SpatialStrategy::Cooperate, SpatialStrategy::Defect) => payoff_matrix.s as f32,
-            (SpatialStrategy::Defect, SpatialStrategy::Cooperate) => payoff_matrix.t as f32,
-            (SpatialStrategy::Defect, SpatialStrategy::Defect) => payoff_matrix.p as f32,
-        }
-    };
-
-    let mutate = |strategy: &SpatialStrategy| -> SpatialStrategy {
-        if rand::random::<f64>() < noise {
-            match strategy {
-                SpatialStrategy::Cooperate => SpatialStrategy::Defect,
-                SpatialStrategy::Defect => SpatialStrategy::Cooperate,
-            }
-        } else {
-            strategy.clone()
-        }
-    };
-
-    grid.next_generation(play_match, mutate);
-
-    Ok(grid.to_byte_array())
 }
 
-#[tauri::command]
-fn paint_spatial_grid(
-    x: usize,
-    y: usize,
-    strategy_val: u8,
-    brush_size: usize,
-    state: tauri::State<'_, GameState>
-) -> Result<Vec<u8>, String> {
-    let mut grid = state.spatial_grid.lock().map_err(|e| e.to_string())?;
-
-    if grid.width == 0 {
-        return Err("Grid not initialized.".to_string());
+impl PayoffMatrix {
+    /// Maps a mean per-round payoff onto [0, 1], from the worst matrix entry to the best,
+    /// so scores under different matrices compare. None when every entry is equal.
+    pub fn normalise(&self, per_round: f64) -> Option<f64> {
+        let entries = [self.t, self.r, self.p, self.s];
+        let min = *entries.iter().min()? as f64;
+        let max = *entries.iter().max()? as f64;
+        (max > min).then(|| (per_round - min) / (max - min))
     }
-
-    let new_strat = if strategy_val == 1 {
-        SpatialStrategy::Cooperate
-    } else {
-        SpatialStrategy::Defect
-    };
-
-    let half_brush = brush_size / 2;
-    let start_x = x.saturating_sub(half_brush);
-    let start_y = y.saturating_sub(half_brush);
-    let end_x = (x + half_brush).min(grid.width.saturating_sub(1));
-    let end_y = (y + half_brush).min(grid.height.saturating_sub(1));
-
-    for i in start_x..=end_x {
-        for j in start_y..=end_y {
-            grid.set(i, j, new_strat.clone());
-        }
-    }
-
-    Ok(grid.to_byte_array())
 }
 
-#[tauri::command]
-fn greet_engine() -> String {
-    "Core Engine: v0.4.0 (Custom Payoff Ready)".to_string()
+/// The noiseless Prisoner's Dilemma most unit tests play, cut to `rounds`.
+#[cfg(test)]
+pub(crate) fn test_config(rounds: u32) -> MatchConfig {
+    MatchConfig { rounds, ..MatchConfig::default() }
 }
 
+// --- 5. Tauri App ---
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder
-        ::default()
-        .manage(GameState {
-            spatial_grid: Mutex::new(SpatialGrid::new(0, 0, SpatialStrategy::Cooperate)),
-        })
-        .invoke_handler(
-            tauri::generate_handler![
-                greet_engine,
-                run_game,
-                run_tournament,
-                run_evolution,
-                init_spatial_grid,
-                step_spatial_grid,
-                paint_spatial_grid
-            ]
-        )
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+    commands::run();
 }
@@ -0,0 +1,273 @@
+use serde::{ Deserialize, Serialize };
+
+use crate::registry::{ check_strategy, memory_one_of };
+use crate::{
+    calculate_payoff,
+    Action,
+    EngineError,
+    MatchConfig,
+    MemoryOne,
+    NoiseSchedule,
+    Round,
+    StrategySpec,
+};
+
+/// Round outcomes from the first player's side, in the order used for distributions.
+const STATES: [Round; 4] = [
+    (Action::Cooperate, Action::Cooperate),
+    (Action::Cooperate, Action::Defect),
+    (Action::Defect, Action::Cooperate),
+    (Action::Defect, Action::Defect),
+];
+
+/// Exact expectations for one match between two memory-one strategies, with no sampling.
+/// Per-round values are expectations of a match's own ratio, as a simulation would average them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExactMatch {
+    pub expected_rounds: f64,
+    pub player_score: f64,
+    pub opponent_score: f64,
+    pub player_per_round: f64,
+    pub opponent_per_round: f64,
+    pub player_cooperation: f64,
+    pub opponent_cooperation: f64,
+    pub player_discounted_score: Option<f64>,
+    pub opponent_discounted_score: Option<f64>,
+    /// Long-run distribution over (C, C), (C, D), (D, C), (D, D); None when it is not unique
+    /// (e.g. without noise, where the start decides everything) or the noise is scheduled.
+    pub stationary: Option<[f64; 4]>,
+    /// Per-round payoffs of (player, opponent) under `stationary`.
+    pub long_run_payoffs: Option<(f64, f64)>,
+}
+
+/// Chance that `strategy` plays C after the true round `last`, which it misreads the opponent's
+/// half of with probability `misread`, and whose intention is flipped with probability `noise`.
+fn cooperation(strategy: &MemoryOne, last: Option<Round>, misread: f64, noise: f64) -> f64 {
+    let intended = match last {
+        None => strategy.p_first,
+        Some((mine, theirs)) => {
+            (1.0 - misread) * strategy.cooperation_probability(Some((mine, theirs))) +
+                misread * strategy.cooperation_probability(Some((mine, theirs.toggle())))
+        }
+    };
+    intended * (1.0 - noise) + (1.0 - intended) * noise
+}
+
+/// Distribution over `STATES` when the players cooperate independently with q1 and q2.
+fn joint(q1: f64, q2: f64) -> [f64; 4] {
+    [q1 * q2, q1 * (1.0 - q2), (1.0 - q1) * q2, (1.0 - q1) * (1.0 - q2)]
+}
+
+/// Transition matrix between consecutive rounds under the given execution noise rates.
+fn transitions(
+    p1: &MemoryOne,
+    p2: &MemoryOne,
+    misread: f64,
+    (noise1, noise2): (f64, f64)
+) -> [[f64; 4]; 4] {
+    STATES.map(|(a1, a2)| {
+        let q1 = cooperation(p1, Some((a1, a2)), misread, noise1);
+        let q2 = cooperation(p2, Some((a2, a1)), misread, noise2);
+        joint(q1, q2)
+    })
+}
+
+fn step(dist: &[f64; 4], m: &[[f64; 4]; 4]) -> [f64; 4] {
+    std::array::from_fn(|j| (0..4).map(|i| dist[i] * m[i][j]).sum())
+}
+
+/// Solves pi M = pi with sum(pi) = 1 by Gauss-Jordan elimination; None when the chain has
+/// more than one stationary distribution.
+fn stationary(m: &[[f64; 4]; 4]) -> Option<[f64; 4]> {
+    // rows of (M^T - I) sum to zero, so one of them can give way to the normalisation
+    let mut a = [[0.0; 5]; 4];
+    for (i, row) in a.iter_mut().enumerate().take(3) {
+        for (j, cell) in row.iter_mut().enumerate().take(4) {
+            *cell = m[j][i] - (if i == j { 1.0 } else { 0.0 });
+        }
+    }
+    a[3] = [1.0; 5];
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        let pivot_row = a[col];
+        for (r, row) in a.iter_mut().enumerate() {
+            if r != col {
+                let factor = row[col] / pivot_row[col];
+                for (x, p) in row.iter_mut().zip(pivot_row).skip(col) {
+                    *x -= factor * p;
+                }
+            }
+        }
+    }
+    Some(std::array::from_fn(|i| (a[i][4] / a[i][i]).max(0.0)))
+}
+
+/// Exact expected outcome of `p1` against `p2` under `config`, by propagating the distribution
+/// over round outcomes through the match. Handles every kind of noise, continuation and discount.
+/// `config` is assumed valid (see `MatchConfig::validate`).
+pub fn exact_match(p1: &MemoryOne, p2: &MemoryOne, config: MatchConfig) -> ExactMatch {
+    let matrix = &config.payoff_matrix;
+    let payoffs = STATES.map(|(a1, a2)| calculate_payoff(a1, a2, matrix));
+    let misread = config.perception_noise;
+    let w = config.continuation.unwrap_or(1.0);
+
+    let (noise1, noise2) = config.noise_at(0);
+    let mut dist = joint(cooperation(p1, None, misread, noise1), cooperation(p2, None, misread, noise2));
+    // `alive` is the chance round t is played; cumulative sums are over the first t + 1 rounds
+    let mut alive = 1.0;
+    let mut weight = 1.0;
+    let mut expected_rounds = 0.0;
+    let mut scores = (0.0, 0.0);
+    let mut discounted = (0.0, 0.0);
+    let mut cumulative = [0.0; 4];
+    let mut per_round = [0.0; 4];
+    for t in 0..config.rounds {
+        if t > 0 {
+            dist = step(&dist, &transitions(p1, p2, misread, config.noise_at(t)));
+        }
+        let s1: f64 = (0..4).map(|k| dist[k] * (payoffs[k].0 as f64)).sum();
+        let s2: f64 = (0..4).map(|k| dist[k] * (payoffs[k].1 as f64)).sum();
+        let round = [s1, s2, dist[0] + dist[1], dist[0] + dist[2]];
+
+        expected_rounds += alive;
+        scores.0 += alive * s1;
+        scores.1 += alive * s2;
+        discounted.0 += alive * weight * s1;
+        discounted.1 += alive * weight * s2;
+        if let Some(discount) = config.discount {
+            weight *= discount;
+        }
+
+        // the match length is independent of the moves, so E[X / L] sums E[X_l] / l over P(L = l)
+        let ends_here = if t + 1 == config.rounds { alive } else { alive * (1.0 - w) };
+        for k in 0..4 {
+            cumulative[k] += round[k];
+            per_round[k] += (ends_here * cumulative[k]) / ((t + 1) as f64);
+        }
+        alive *= w;
+    }
+
+    let stationary = match config.noise_schedule {
+        NoiseSchedule::Constant => stationary(&transitions(p1, p2, misread, config.noise_at(0))),
+        _ => None,
+    };
+    let long_run_payoffs = stationary.map(|pi| {
+        (
+            (0..4).map(|k| pi[k] * (payoffs[k].0 as f64)).sum(),
+            (0..4).map(|k| pi[k] * (payoffs[k].1 as f64)).sum(),
+        )
+    });
+
+    ExactMatch {
+        expected_rounds,
+        player_score: scores.0,
+        opponent_score: scores.1,
+        player_per_round: per_round[0],
+        opponent_per_round: per_round[1],
+        player_cooperation: per_round[2],
+        opponent_cooperation: per_round[3],
+        player_discounted_score: config.discount.map(|_| discounted.0),
+        opponent_discounted_score: config.discount.map(|_| discounted.1),
+        stationary,
+        long_run_payoffs,
+    }
+}
+
+/// `exact_match` for two roster entries, which must both be memory-one strategies.
+pub fn exact_game(
+    p1: &StrategySpec,
+    p2: &StrategySpec,
+    config: MatchConfig
+) -> Result<ExactMatch, EngineError> {
+    config.validate()?;
+    let as_memory_one = |param: &str, spec: &StrategySpec| {
+        check_strategy(spec, &config.payoff_matrix)?;
+        memory_one_of(spec, &config.payoff_matrix)?.ok_or_else(|| EngineError::InvalidParameter {
+            param: param.to_string(),
+            value: spec.id.clone(),
+            reason: "is not a memory-one strategy".to_string(),
+        })
+    };
+    Ok(exact_match(&as_memory_one("p1", p1)?, &as_memory_one("p2", p2)?, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ build_strategy, play_match, seeded_rng, test_config };
+
+    fn spec(s: &str) -> StrategySpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_deterministic_pair_matches_the_simulation_exactly() {
+        let exact = exact_game(&spec("tit_for_tat"), &spec("always_defect"), test_config(50)).unwrap();
+
+        assert_eq!(exact.expected_rounds, 50.0);
+        assert!((exact.player_score - 49.0).abs() < 1e-9);
+        assert!((exact.opponent_score - 54.0).abs() < 1e-9);
+        assert!((exact.player_cooperation - 0.02).abs() < 1e-12);
+        // ALLD absorbs at mutual defection
+        assert_eq!(exact.stationary, Some([0.0, 0.0, 0.0, 1.0]));
+
+        // TFT vs TFT never leaves (C, C), but (D, D) would be just as stable
+        let mirror = exact_game(&spec("tit_for_tat"), &spec("tit_for_tat"), test_config(50)).unwrap();
+        assert!(mirror.stationary.is_none());
+        assert!((mirror.player_score - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_noisy_tft_pair_spends_a_quarter_of_the_time_in_each_state() {
+        let noisy = MatchConfig { noise: 0.05, ..test_config(50) };
+        let exact = exact_game(&spec("tit_for_tat"), &spec("tit_for_tat"), noisy).unwrap();
+
+        let pi = exact.stationary.unwrap();
+        assert!(pi.iter().all(|&p| (p - 0.25).abs() < 1e-9), "{:?}", pi);
+        assert!((exact.long_run_payoffs.unwrap().0 - 2.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulation_converges_to_the_exact_expectation() {
+        let cfg = MatchConfig {
+            noise: 0.02,
+            opponent_noise: Some(0.1),
+            perception_noise: 0.05,
+            continuation: Some(0.95),
+            discount: Some(0.9),
+            ..test_config(50)
+        };
+        let (a, b) = (spec("generous_tft(forgiveness=0.3)"), spec("memory_one(p_cd=0.5,p_dc=0.2,p_dd=0.7)"));
+        let exact = exact_game(&a, &b, cfg).unwrap();
+
+        let mut p1 = build_strategy(&a).unwrap();
+        let mut p2 = build_strategy(&b).unwrap();
+        let mut rng = seeded_rng(Some(5));
+        let matches = 20_000;
+        let (mut score, mut per_round, mut discounted, mut rounds) = (0.0, 0.0, 0.0, 0.0);
+        for _ in 0..matches {
+            let result = play_match(p1.as_mut(), p2.as_mut(), cfg, &mut rng);
+            let len = result.rounds.len() as f64;
+            score += result.player_score as f64;
+            per_round += (result.opponent_score as f64) / len;
+            discounted += result.player_discounted_score.unwrap();
+            rounds += len;
+        }
+        let n = matches as f64;
+
+        assert!((rounds / n - exact.expected_rounds).abs() < 0.02 * exact.expected_rounds);
+        assert!((score / n - exact.player_score).abs() < 0.03 * exact.player_score);
+        assert!((per_round / n - exact.opponent_per_round).abs() < 0.02);
+        let expected_discounted = exact.player_discounted_score.unwrap();
+        assert!((discounted / n - expected_discounted).abs() < 0.02 * expected_discounted);
+    }
+
+    #[test]
+    fn test_strategies_with_longer_memory_are_rejected() {
+        assert!(exact_game(&spec("grim_trigger"), &spec("tit_for_tat"), test_config(50)).is_err());
+    }
+}
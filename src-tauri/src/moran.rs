@@ -0,0 +1,288 @@
+use serde::{ Deserialize, Serialize };
+use rand::prelude::*;
+use rayon::prelude::*;
+
+use crate::evolution::{ finite_fitness, initial_counts, payoff_baseline, pick };
+use crate::payoffs::{ expected_payoffs, expected_payoffs_among };
+use crate::validation::{
+    check_count,
+    check_probability,
+    MAX_MORAN_STEPS,
+    MAX_POPULATION,
+    MAX_REPETITIONS,
+};
+use crate::{ check_strategy, create_strategy, field_ids, stream_rng, EngineError, MatchConfig, StrategySpec };
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MoranConfig {
+    /// w in fitness = 1 - w + w * payoff; small values give weak selection, 0 is neutral drift.
+    pub selection_intensity: f64,
+    /// A run that has not fixated after this many birth-death steps is reported as unresolved.
+    pub max_steps: u64,
+    /// Matches averaged into each expected payoff when a pairing involves randomness.
+    pub payoff_repetitions: u32,
+    /// Compute memory-one pairs from their Markov chain instead of sampling them.
+    pub exact: bool,
+}
+
+impl Default for MoranConfig {
+    fn default() -> Self {
+        MoranConfig { selection_intensity: 1.0, max_steps: 10_000_000, payoff_repetitions: 1, exact: false }
+    }
+}
+
+impl MoranConfig {
+    pub fn validate(&self) -> Result<(), EngineError> {
+        check_probability("selection_intensity", self.selection_intensity)?;
+        check_count("max_steps", self.max_steps, MAX_MORAN_STEPS)?;
+        check_count("payoff_repetitions", self.payoff_repetitions as u64, MAX_REPETITIONS as u64)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoranRun {
+    /// The strategy that took over the whole population, or None if `max_steps` ran out first.
+    pub fixated: Option<String>,
+    /// Birth-death steps taken; divide by the population size for generations.
+    pub fixation_time: u64,
+    pub final_populations: Vec<(String, u32)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FixationEstimate {
+    pub resident: String,
+    pub mutant: String,
+    pub population_size: u32,
+    pub repetitions: u32,
+    pub mutant_fixations: u32,
+    pub resident_fixations: u32,
+    pub unresolved: u32,
+    /// Share of repetitions in which the single mutant took over.
+    pub fixation_probability: f64,
+    /// 1/N, the fixation probability of a neutral mutant, for comparison.
+    pub neutral_probability: f64,
+    /// Mean steps to fixation over the runs the mutant won.
+    pub mean_fixation_time: Option<f64>,
+}
+
+/// Birth-death steps until one strategy remains: a reproducer is picked in proportion to
+/// count × fitness and its offspring replaces a uniformly random individual.
+fn run_to_fixation(
+    counts: &mut [u32],
+    payoffs: &[Vec<f64>],
+    baseline: f64,
+    moran: MoranConfig,
+    rng: &mut StdRng
+) -> (Option<usize>, u64) {
+    let w = moran.selection_intensity;
+    let mut steps = 0;
+    loop {
+        let active: Vec<usize> = (0..counts.len()).filter(|&i| counts[i] > 0).collect();
+        if active.len() == 1 {
+            return (Some(active[0]), steps);
+        }
+        if steps >= moran.max_steps {
+            return (None, steps);
+        }
+
+        let fitness = finite_fitness(counts, &active, payoffs);
+        let birth_weights: Vec<f64> = counts
+            .iter()
+            .zip(&fitness)
+            .map(|(&n, f)| (n as f64) * (1.0 - w + w * (f - baseline)))
+            .collect();
+        let death_weights: Vec<f64> = counts
+            .iter()
+            .map(|&n| n as f64)
+            .collect();
+        let born = pick(&birth_weights, counts, rng);
+        let died = pick(&death_weights, counts, rng);
+        counts[born] += 1;
+        counts[died] -= 1;
+        steps += 1;
+    }
+}
+
+/// One Moran process over the default field, until a single strategy has fixated.
+/// Pairwise payoffs are sampled once up front, since every step reuses them, and only
+/// between strategies present at the start: the others can never appear.
+pub fn run_moran(
+    config: MatchConfig,
+    moran: MoranConfig,
+    initial_populations: Vec<u32>,
+    rng: &mut StdRng
+) -> Result<MoranRun, EngineError> {
+    config.validate()?;
+    moran.validate()?;
+    let all_ids = field_ids();
+    for id in &all_ids {
+        check_strategy(&StrategySpec::new(id), &config.payoff_matrix)?;
+    }
+    let mut counts = initial_counts(initial_populations, &all_ids)?;
+    let names = all_ids
+        .iter()
+        .map(|id| create_strategy(id).map(|s| s.name()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let present: Vec<usize> = (0..counts.len()).filter(|&i| counts[i] > 0).collect();
+    let payoffs = expected_payoffs_among(&all_ids, &present, config, moran.payoff_repetitions, moran.exact, rng)?;
+    let baseline = payoff_baseline(&config.payoff_matrix);
+    let (fixated, fixation_time) = run_to_fixation(&mut counts, &payoffs, baseline, moran, rng);
+
+    Ok(MoranRun {
+        fixated: fixated.map(|i| names[i].clone()),
+        fixation_time,
+        final_populations: names.into_iter().zip(counts).collect(),
+    })
+}
+
+/// Estimates the probability that one `mutant` takes over a population of `resident`s.
+/// Repetitions run in parallel on their own streams split off `rng`.
+pub fn estimate_fixation(
+    config: MatchConfig,
+    moran: MoranConfig,
+    resident: &str,
+    mutant: &str,
+    population_size: u32,
+    repetitions: u32,
+    rng: &mut StdRng
+) -> Result<FixationEstimate, EngineError> {
+    config.validate()?;
+    moran.validate()?;
+    check_count("population_size", population_size as u64, MAX_POPULATION as u64)?;
+    if population_size < 2 {
+        return Err(EngineError::InvalidParameter {
+            param: "population_size".to_string(),
+            value: population_size.to_string(),
+            reason: "needs room for a resident and a mutant".to_string(),
+        });
+    }
+    check_count("repetitions", repetitions as u64, MAX_REPETITIONS as u64)?;
+
+    let ids = [resident, mutant];
+    for id in &ids {
+        check_strategy(&StrategySpec::new(id), &config.payoff_matrix)?;
+    }
+    let payoffs = expected_payoffs(&ids, config, moran.payoff_repetitions, moran.exact, rng)?;
+    let baseline = payoff_baseline(&config.payoff_matrix);
+    let base: u64 = rng.random();
+    let runs: Vec<(Option<usize>, u64)> = (0..repetitions)
+        .into_par_iter()
+        .map(|rep| {
+            let mut counts = [population_size - 1, 1];
+            run_to_fixation(&mut counts, &payoffs, baseline, moran, &mut stream_rng(base, rep as u64))
+        })
+        .collect();
+
+    let mut mutant_fixations = 0;
+    let mut resident_fixations = 0;
+    let mut mutant_time = 0;
+    for run in runs {
+        match run {
+            (Some(1), steps) => {
+                mutant_fixations += 1;
+                mutant_time += steps;
+            }
+            (Some(_), _) => {
+                resident_fixations += 1;
+            }
+            (None, _) => {}
+        }
+    }
+
+    Ok(FixationEstimate {
+        resident: create_strategy(resident)?.name(),
+        mutant: create_strategy(mutant)?.name(),
+        population_size,
+        repetitions,
+        mutant_fixations,
+        resident_fixations,
+        unresolved: repetitions - mutant_fixations - resident_fixations,
+        fixation_probability: (mutant_fixations as f64) / (repetitions as f64),
+        neutral_probability: 1.0 / (population_size as f64),
+        mean_fixation_time: (mutant_fixations > 0).then(
+            || (mutant_time as f64) / (mutant_fixations as f64)
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ seeded_rng, test_config };
+
+    fn moran(selection_intensity: f64) -> MoranConfig {
+        MoranConfig { selection_intensity, max_steps: 1_000_000, ..MoranConfig::default() }
+    }
+
+    #[test]
+    fn test_neutral_mutant_fixates_with_probability_one_over_n() {
+        let estimate = estimate_fixation(
+            test_config(10),
+            moran(1.0),
+            "always_defect",
+            "always_defect",
+            10,
+            4000,
+            &mut seeded_rng(Some(7))
+        ).unwrap();
+
+        assert_eq!(estimate.unresolved, 0);
+        assert!((estimate.fixation_probability - 0.1).abs() < 0.02);
+        assert!(estimate.mean_fixation_time.is_some());
+    }
+
+    #[test]
+    fn test_defector_invades_cooperators_more_often_than_drift() {
+        let estimate = estimate_fixation(
+            test_config(10),
+            moran(1.0),
+            "always_cooperate",
+            "always_defect",
+            10,
+            2000,
+            &mut seeded_rng(Some(7))
+        ).unwrap();
+
+        assert!(estimate.fixation_probability > 2.0 * estimate.neutral_probability);
+    }
+
+    #[test]
+    fn test_moran_config_fills_in_defaults() {
+        let partial: MoranConfig = serde_json::from_str(r#"{"payoff_repetitions": 20}"#).unwrap();
+        assert_eq!(partial.selection_intensity, 1.0);
+        assert_eq!(partial.payoff_repetitions, 20);
+
+        let no_samples = MoranConfig { payoff_repetitions: 0, ..MoranConfig::default() };
+        assert!(run_moran(test_config(10), no_samples, vec![], &mut seeded_rng(Some(1))).is_err());
+    }
+
+    #[test]
+    fn test_run_moran_rejects_oversized_populations() {
+        let huge = vec![MAX_POPULATION; field_ids().len()];
+        match run_moran(test_config(10), moran(1.0), huge, &mut seeded_rng(Some(1))) {
+            Err(EngineError::InvalidParameter { param, .. }) => assert_eq!(param, "initial_populations"),
+            other => panic!("an oversized population was accepted: {:?}", other.map(|r| r.fixation_time)),
+        }
+    }
+
+    #[test]
+    fn test_run_moran_reports_the_fixated_strategy() {
+        let initial: Vec<u32> = field_ids()
+            .iter()
+            .map(|&id| if id == "always_defect" || id == "always_cooperate" { 5 } else { 0 })
+            .collect();
+        let run = run_moran(test_config(10), moran(1.0), initial, &mut seeded_rng(Some(3))).unwrap();
+
+        let winner = run.fixated.expect("a two-strategy population fixates");
+        let survivors: Vec<&(String, u32)> = run.final_populations
+            .iter()
+            .filter(|(_, n)| *n > 0)
+            .collect();
+        assert_eq!(survivors.len(), 1);
+        assert_eq!(survivors[0].0, winner);
+        assert_eq!(survivors[0].1, 10);
+        assert!(run.fixation_time > 0);
+    }
+}
@@ -0,0 +1,105 @@
+use rand::prelude::*;
+use rayon::prelude::*;
+
+use crate::markov::exact_match;
+use crate::registry::{ find_strategy, memory_one_of, StrategyTag };
+use crate::validation::{ check_count, MAX_REPETITIONS };
+use crate::{ create_strategy, play_match, stream_rng, EngineError, MatchConfig, StrategySpec };
+
+/// True when a single match already gives the exact payoff, so repetitions would be wasted.
+fn is_deterministic(a: &str, b: &str, config: &MatchConfig) -> Result<bool, EngineError> {
+    let stochastic = |id: &str| {
+        find_strategy(id).map(|e| e.info.tags.contains(&StrategyTag::Stochastic))
+    };
+    Ok(config.is_noiseless() && config.continuation.is_none() && !stochastic(a)? && !stochastic(b)?)
+}
+
+/// Mean per-round payoff of `ids[i]` against `ids[j]` for every ordered pair within `active`,
+/// averaged over `repetitions` matches; pairs outside `active` are left at 0.
+/// With `exact`, memory-one pairs are computed from their Markov chain instead of sampled.
+/// Pairs are played in parallel, each on its own stream split off `rng`.
+pub(crate) fn expected_payoffs_among(
+    ids: &[&str],
+    active: &[usize],
+    config: MatchConfig,
+    repetitions: u32,
+    exact: bool,
+    rng: &mut StdRng
+) -> Result<Vec<Vec<f64>>, EngineError> {
+    check_count("payoff_repetitions", repetitions as u64, MAX_REPETITIONS as u64)?;
+    let base: u64 = rng.random();
+    let pairs: Vec<(usize, usize)> = active
+        .iter()
+        .flat_map(|&i| active.iter().map(move |&j| (i, j)))
+        .collect();
+    let means = pairs
+        .par_iter()
+        .map(|&(i, j)| {
+            if exact {
+                let memory_one = |id: &str| memory_one_of(&StrategySpec::new(id), &config.payoff_matrix);
+                if let (Some(m1), Some(m2)) = (memory_one(ids[i])?, memory_one(ids[j])?) {
+                    return Ok(exact_match(&m1, &m2, config).player_per_round);
+                }
+            }
+            let samples = if is_deterministic(ids[i], ids[j], &config)? { 1 } else { repetitions };
+            let mut rng = stream_rng(base, (i * ids.len() + j) as u64);
+            let mut p1 = create_strategy(ids[i])?;
+            let mut p2 = create_strategy(ids[j])?;
+            let mut sum = 0.0;
+            for _ in 0..samples {
+                let result = play_match(p1.as_mut(), p2.as_mut(), config, &mut rng);
+                sum += (result.player_score as f64) / (result.rounds.len() as f64);
+            }
+            Ok(sum / (samples as f64))
+        })
+        .collect::<Result<Vec<f64>, EngineError>>()?;
+
+    let mut payoffs = vec![vec![0.0; ids.len()]; ids.len()];
+    for (&(i, j), mean) in pairs.iter().zip(means) {
+        payoffs[i][j] = mean;
+    }
+    Ok(payoffs)
+}
+
+/// The N×N expected-payoff matrix: entry [i][j] is the mean per-round payoff of `ids[i]`
+/// against `ids[j]`. It does not depend on population counts, so dynamics can compute it once.
+pub fn expected_payoffs(
+    ids: &[&str],
+    config: MatchConfig,
+    repetitions: u32,
+    exact: bool,
+    rng: &mut StdRng
+) -> Result<Vec<Vec<f64>>, EngineError> {
+    config.validate()?;
+    let all: Vec<usize> = (0..ids.len()).collect();
+    expected_payoffs_among(ids, &all, config, repetitions, exact, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ seeded_rng, test_config };
+
+    #[test]
+    fn test_expected_payoffs_are_per_round_means() {
+        let ids = ["tit_for_tat", "always_defect", "random"];
+        let payoffs = expected_payoffs(&ids, test_config(10), 400, false, &mut seeded_rng(Some(5))).unwrap();
+
+        assert_eq!(payoffs[0][0], 3.0);
+        assert_eq!(payoffs[0][1], 0.9); // one sucker round, then nine mutual defections
+        assert_eq!(payoffs[1][0], 1.4);
+        // ALLD against a fair coin: (5 + 1) / 2 on average
+        assert!((payoffs[1][2] - 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_exact_payoffs_are_opt_in() {
+        let ids = ["always_defect", "random"];
+        let exact = expected_payoffs(&ids, test_config(10), 1, true, &mut seeded_rng(Some(5))).unwrap();
+        assert!((exact[0][1] - 3.0).abs() < 1e-12);
+
+        // without it, one ten-round match is played: 1 + 0.4 per round the coin cooperated
+        let sampled = expected_payoffs(&ids, test_config(10), 1, false, &mut seeded_rng(Some(5))).unwrap();
+        assert!(((sampled[0][1] - 1.0) / 0.4 - ((sampled[0][1] - 1.0) / 0.4).round()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,702 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::validation::check_range;
+use crate::{
+    AlwaysCooperate,
+    AlwaysDefect,
+    EngineError,
+    FsmDefinition,
+    FsmStrategy,
+    GenerousTFT,
+    GrimTrigger,
+    Joss,
+    MemoryOne,
+    Pavlov,
+    PayoffMatrix,
+    Random,
+    Strategy,
+    TitForTat,
+    ZeroDeterminant,
+    ZeroDeterminantStrategy,
+};
+
+/// Coarse behavioural labels, used by the frontend for filtering and colouring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyTag {
+    /// Never the first to defect.
+    Nice,
+    /// Answers a defection with a defection.
+    Retaliatory,
+    /// Draws from the RNG, so repeated matches differ unless seeded.
+    Stochastic,
+}
+
+impl StrategyTag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StrategyTag::Nice => "nice",
+            StrategyTag::Retaliatory => "retaliatory",
+            StrategyTag::Stochastic => "stochastic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamInfo {
+    pub name: &'static str,
+    pub default: f64,
+    /// Inclusive bounds; `build_strategy` rejects values outside them.
+    pub range: (f64, f64),
+    pub description: &'static str,
+}
+
+/// Everything the frontend needs to know about a strategy, without instantiating it.
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub tags: &'static [StrategyTag],
+    /// Whether it joins the default tournament field and the evolution populations. Families
+    /// whose defaults duplicate another strategy are only used where a roster names them.
+    pub default_field: bool,
+    pub params: &'static [ParamInfo],
+}
+
+/// Resolved parameter values by name; builders can rely on every declared parameter being present.
+pub type StrategyParams = BTreeMap<String, f64>;
+
+type FeasibilityCheck = fn(&StrategyParams, &PayoffMatrix) -> Result<(), EngineError>;
+
+pub struct StrategyEntry {
+    pub info: StrategyInfo,
+    build: fn(&StrategyParams) -> Box<dyn Strategy>,
+    /// The same behaviour as a memory-one strategy, for strategies that are one; the
+    /// payoff matrix matters for those that react to their own payoff, like Pavlov.
+    memory_one: Option<fn(&StrategyParams, &PayoffMatrix) -> MemoryOne>,
+    /// Rejects parameters that cannot be honoured under a given payoff matrix, for strategies
+    /// whose behaviour is derived from it.
+    feasible: Option<FeasibilityCheck>,
+}
+
+impl StrategyEntry {
+    pub fn default_params(&self) -> StrategyParams {
+        self.info.params
+            .iter()
+            .map(|p| (p.name.to_string(), p.default))
+            .collect()
+    }
+
+    pub fn build(&self, params: &StrategyParams) -> Box<dyn Strategy> {
+        (self.build)(params)
+    }
+
+    pub fn memory_one(&self, params: &StrategyParams, matrix: &PayoffMatrix) -> Option<MemoryOne> {
+        self.memory_one.map(|f| f(params, matrix))
+    }
+
+    pub fn check(&self, params: &StrategyParams, matrix: &PayoffMatrix) -> Result<(), EngineError> {
+        self.feasible.map_or(Ok(()), |f| f(params, matrix))
+    }
+}
+
+const fn m1(p_first: f64, p_cc: f64, p_cd: f64, p_dc: f64, p_dd: f64) -> MemoryOne {
+    MemoryOne { p_first, p_cc, p_cd, p_dc, p_dd }
+}
+
+/// Win-stay, lose-shift against the matrix: a round paying at least R is repeated.
+fn pavlov_memory_one(matrix: &PayoffMatrix) -> MemoryOne {
+    let stay = |payoff: i32| payoff >= matrix.r;
+    let p = |cooperated: bool, payoff: i32| if cooperated == stay(payoff) { 1.0 } else { 0.0 };
+    m1(1.0, p(true, matrix.r), p(true, matrix.s), p(false, matrix.t), p(false, matrix.p))
+}
+
+use StrategyTag::{ Nice, Retaliatory, Stochastic };
+
+/// The single source of truth for built-in strategies. Order is display order, and the order
+/// of the default tournament field and evolution populations.
+static REGISTRY: [StrategyEntry; 10] = [
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "tit_for_tat",
+            name: "Tit-For-Tat",
+            description: "Starts with cooperation, then mimics your last move.",
+            tags: &[Nice, Retaliatory],
+            default_field: true,
+            params: &[],
+        },
+        build: |_| Box::new(TitForTat),
+        memory_one: Some(|_, _| m1(1.0, 1.0, 0.0, 1.0, 0.0)),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "always_defect",
+            name: "Always Defect",
+            description: "The agent of chaos. Never cooperates.",
+            tags: &[],
+            default_field: true,
+            params: &[],
+        },
+        build: |_| Box::new(AlwaysDefect),
+        memory_one: Some(|_, _| m1(0.0, 0.0, 0.0, 0.0, 0.0)),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "grim_trigger",
+            name: "Grim Trigger",
+            description: "Cooperates until you cross him ONCE. Then never forgives.",
+            tags: &[Nice, Retaliatory],
+            default_field: true,
+            params: &[],
+        },
+        build: |_| Box::new(GrimTrigger::default()),
+        memory_one: None,
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "always_cooperate",
+            name: "Always Cooperate",
+            description: "The saint. Always cooperates.",
+            tags: &[Nice],
+            default_field: true,
+            params: &[],
+        },
+        build: |_| Box::new(AlwaysCooperate),
+        memory_one: Some(|_, _| m1(1.0, 1.0, 1.0, 1.0, 1.0)),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "random",
+            name: "Random",
+            description: "Unpredictable. Flips a coin every time.",
+            tags: &[Stochastic],
+            default_field: true,
+            params: &[
+                ParamInfo {
+                    name: "cooperation_probability",
+                    default: 0.5,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating on any move.",
+                },
+            ],
+        },
+        build: |p| Box::new(Random { cooperation_probability: p["cooperation_probability"] }),
+        memory_one: Some(|p, _| {
+            let c = p["cooperation_probability"];
+            m1(c, c, c, c, c)
+        }),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "pavlov",
+            name: "Pavlov",
+            description: "Win-Stay, Lose-Shift. Changes strategy only when it fails.",
+            tags: &[Nice, Retaliatory],
+            default_field: true,
+            params: &[],
+        },
+        build: |_| Box::new(Pavlov),
+        memory_one: Some(|_, m| pavlov_memory_one(m)),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "generous_tft",
+            name: "Generous TFT",
+            description: "Like TFT, but forgives mistakes with 10% probability to stop death spirals.",
+            tags: &[Nice, Retaliatory, Stochastic],
+            default_field: true,
+            params: &[
+                ParamInfo {
+                    name: "forgiveness",
+                    default: 0.1,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating after the opponent defected.",
+                },
+            ],
+        },
+        build: |p| Box::new(GenerousTFT { forgiveness: p["forgiveness"] }),
+        memory_one: Some(|p, _| m1(1.0, 1.0, p["forgiveness"], 1.0, p["forgiveness"])),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "joss",
+            name: "Joss",
+            description: "Sneaky. Mostly TFT, but tries to defect 10% of the time to exploit you.",
+            tags: &[Retaliatory, Stochastic],
+            default_field: true,
+            params: &[
+                ParamInfo {
+                    name: "sneakiness",
+                    default: 0.1,
+                    range: (0.0, 1.0),
+                    description: "Chance of defecting after the opponent cooperated.",
+                },
+            ],
+        },
+        build: |p| Box::new(Joss { sneakiness: p["sneakiness"] }),
+        memory_one: Some(|p, _| m1(1.0, 1.0 - p["sneakiness"], 0.0, 1.0 - p["sneakiness"], 0.0)),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "memory_one",
+            name: "Memory-One",
+            description: "Cooperates with a set chance after each outcome of the last round. Starts out as Tit-For-Tat.",
+            tags: &[Stochastic],
+            default_field: false,
+            params: &[
+                ParamInfo {
+                    name: "p_first",
+                    default: 1.0,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating on the first move.",
+                },
+                ParamInfo {
+                    name: "p_cc",
+                    default: 1.0,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating after both cooperated.",
+                },
+                ParamInfo {
+                    name: "p_cd",
+                    default: 0.0,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating after being suckered.",
+                },
+                ParamInfo {
+                    name: "p_dc",
+                    default: 1.0,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating after exploiting the opponent.",
+                },
+                ParamInfo {
+                    name: "p_dd",
+                    default: 0.0,
+                    range: (0.0, 1.0),
+                    description: "Chance of cooperating after both defected.",
+                },
+            ],
+        },
+        build: |p| {
+            Box::new(MemoryOne {
+                p_first: p["p_first"],
+                p_cc: p["p_cc"],
+                p_cd: p["p_cd"],
+                p_dc: p["p_dc"],
+                p_dd: p["p_dd"],
+            })
+        },
+        memory_one: Some(|p, _| m1(p["p_first"], p["p_cc"], p["p_cd"], p["p_dc"], p["p_dd"])),
+        feasible: None,
+    },
+    StrategyEntry {
+        info: StrategyInfo {
+            id: "zero_determinant",
+            name: "Zero-Determinant",
+            description: "Press-Dyson strategy. Whatever you do, its surplus over the baseline is slope times yours; by default an extortioner.",
+            tags: &[Stochastic],
+            default_field: false,
+            params: &[
+                ParamInfo {
+                    name: "slope",
+                    default: 3.0,
+                    range: (1.0, 100.0),
+                    description: "Extortion factor: its surplus over the baseline per unit of yours.",
+                },
+                ParamInfo {
+                    name: "baseline",
+                    default: 0.0,
+                    range: (0.0, 1.0),
+                    description: "Baseline payoff as a fraction of the way from P (0, extortion) to R (1, generous).",
+                },
+                ParamInfo {
+                    name: "scale",
+                    default: 1.0,
+                    range: (0.0, 1.0),
+                    description: "Fraction of the largest feasible step towards the line; must be above 0.",
+                },
+            ],
+        },
+        build: |p| Box::new(ZeroDeterminantStrategy::new(zero_determinant(p))),
+        memory_one: Some(|p, m| zero_determinant(p).clamped(m)),
+        feasible: Some(|p, m| zero_determinant(p).memory_one(m).map(|_| ())),
+    },
+];
+
+fn zero_determinant(p: &StrategyParams) -> ZeroDeterminant {
+    ZeroDeterminant { slope: p["slope"], baseline: p["baseline"], scale: p["scale"] }
+}
+
+pub fn registry() -> &'static [StrategyEntry] {
+    &REGISTRY
+}
+
+pub fn strategy_ids() -> Vec<&'static str> {
+    REGISTRY.iter()
+        .map(|e| e.info.id)
+        .collect()
+}
+
+/// Ids of the default field, in registry order: the default tournament roster, and the
+/// strategies evolution and Moran runs take one initial count each for.
+pub fn field_ids() -> Vec<&'static str> {
+    REGISTRY.iter()
+        .filter(|e| e.info.default_field)
+        .map(|e| e.info.id)
+        .collect()
+}
+
+pub fn list_strategies() -> Vec<StrategyInfo> {
+    REGISTRY.iter()
+        .map(|e| e.info.clone())
+        .collect()
+}
+
+pub fn find_strategy(id: &str) -> Result<&'static StrategyEntry, EngineError> {
+    REGISTRY.iter()
+        .find(|e| e.info.id == id)
+        .ok_or_else(|| EngineError::UnknownStrategy {
+            id: id.to_string(),
+            valid_ids: strategy_ids()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        })
+}
+
+/// Builds a fresh instance with the registry's default parameters.
+pub fn create_strategy(id: &str) -> Result<Box<dyn Strategy>, EngineError> {
+    let entry = find_strategy(id)?;
+    Ok(entry.build(&entry.default_params()))
+}
+
+/// Id of specs that carry their own state machine instead of naming a registered strategy.
+pub const MACHINE_ID: &str = "fsm";
+
+/// One member of a competing field: a registered id plus optional parameter overrides.
+/// Deserializes from the shorthand `"generous_tft(forgiveness=0.3)"` as well as from
+/// `{ "id": "generous_tft", "params": { "forgiveness": 0.3 }, "label": "GTFT 30%" }`,
+/// or from `{ "machine": { ... } }` for a user-defined `FsmDefinition`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "SpecRepr")]
+pub struct StrategySpec {
+    pub id: String,
+    pub params: StrategyParams,
+    /// Shown instead of the generated name, e.g. to tell two variants apart in a ranking.
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<Box<FsmDefinition>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SpecRepr {
+    Short(String),
+    Machine {
+        machine: FsmDefinition,
+        #[serde(default)]
+        label: Option<String>,
+    },
+    Full {
+        id: String,
+        #[serde(default)]
+        params: StrategyParams,
+        #[serde(default)]
+        label: Option<String>,
+    },
+}
+
+impl TryFrom<SpecRepr> for StrategySpec {
+    type Error = EngineError;
+
+    fn try_from(repr: SpecRepr) -> Result<Self, Self::Error> {
+        match repr {
+            SpecRepr::Short(s) => s.parse(),
+            SpecRepr::Machine { machine, label } => {
+                machine.validate()?;
+                Ok(StrategySpec { label, ..StrategySpec::machine(machine) })
+            }
+            SpecRepr::Full { id, params, label } => Ok(StrategySpec { id, params, label, machine: None }),
+        }
+    }
+}
+
+impl FromStr for StrategySpec {
+    type Err = EngineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = |reason: String| EngineError::InvalidStrategySpec {
+            spec: s.to_string(),
+            reason,
+        };
+
+        let (id, args) = match s.split_once('(') {
+            None => (s, ""),
+            Some((id, rest)) => {
+                let args = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| invalid("missing closing parenthesis".to_string()))?;
+                (id.trim(), args)
+            }
+        };
+        if id.is_empty() {
+            return Err(invalid("empty strategy id".to_string()));
+        }
+
+        let mut params = StrategyParams::new();
+        for pair in args
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty()) {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected name=value, got {:?}", pair)))?;
+            let value = value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("{:?} is not a number", value.trim())))?;
+            params.insert(name.trim().to_string(), value);
+        }
+
+        Ok(StrategySpec { id: id.to_string(), params, label: None, machine: None })
+    }
+}
+
+impl StrategySpec {
+    pub fn new(id: &str) -> Self {
+        StrategySpec { id: id.to_string(), params: StrategyParams::new(), label: None, machine: None }
+    }
+
+    pub fn machine(definition: FsmDefinition) -> Self {
+        StrategySpec {
+            id: MACHINE_ID.to_string(),
+            params: StrategyParams::new(),
+            label: None,
+            machine: Some(Box::new(definition)),
+        }
+    }
+
+    /// The label if set, otherwise the registry name followed by any overridden parameters.
+    pub fn display_name(&self) -> Result<String, EngineError> {
+        if let Some(label) = &self.label {
+            return Ok(label.clone());
+        }
+        if let Some(machine) = &self.machine {
+            return Ok(machine.name.clone());
+        }
+        let name = find_strategy(&self.id)?.info.name;
+        if self.params.is_empty() {
+            return Ok(name.to_string());
+        }
+        let params: Vec<String> = self.params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        Ok(format!("{} ({})", name, params.join(", ")))
+    }
+}
+
+/// The entry and full parameter set a spec resolves to: its overrides on top of the defaults.
+/// Overrides must be declared parameters within their declared range.
+fn resolve(spec: &StrategySpec) -> Result<(&'static StrategyEntry, StrategyParams), EngineError> {
+    let entry = find_strategy(&spec.id)?;
+    let mut params = entry.default_params();
+    for (name, &value) in &spec.params {
+        match params.get_mut(name) {
+            Some(slot) => {
+                let info = entry.info.params
+                    .iter()
+                    .find(|p| p.name == name)
+                    .expect("every default parameter is declared");
+                check_range(name, value, info.range)?;
+                *slot = value;
+            }
+            None => {
+                return Err(EngineError::UnknownParameter {
+                    strategy: spec.id.clone(),
+                    param: name.clone(),
+                    valid_params: entry.info.params
+                        .iter()
+                        .map(|p| p.name.to_string())
+                        .collect(),
+                });
+            }
+        }
+    }
+    Ok((entry, params))
+}
+
+/// Builds a fresh instance with the spec's overrides applied on top of the defaults.
+pub fn build_strategy(spec: &StrategySpec) -> Result<Box<dyn Strategy>, EngineError> {
+    if let Some(machine) = &spec.machine {
+        return Ok(Box::new(FsmStrategy::new(machine)?));
+    }
+    let (entry, params) = resolve(spec)?;
+    Ok(entry.build(&params))
+}
+
+/// Checks that the spec can be played as specified under `matrix`, e.g. that a zero-determinant
+/// strategy's probabilities exist. Everything that fields strategies for a match calls this first.
+pub fn check_strategy(spec: &StrategySpec, matrix: &PayoffMatrix) -> Result<(), EngineError> {
+    if let Some(machine) = &spec.machine {
+        return machine.validate();
+    }
+    let (entry, params) = resolve(spec)?;
+    entry.check(&params, matrix)
+}
+
+/// The spec as a memory-one strategy under `matrix`, or None if it has memory beyond the last round.
+pub fn memory_one_of(spec: &StrategySpec, matrix: &PayoffMatrix) -> Result<Option<MemoryOne>, EngineError> {
+    if spec.machine.is_some() {
+        return Ok(None);
+    }
+    let (entry, params) = resolve(spec)?;
+    Ok(entry.memory_one(&params, matrix))
+}
+
+/// Every strategy of the default field once, with default parameters.
+pub fn default_roster() -> Vec<StrategySpec> {
+    field_ids()
+        .into_iter()
+        .map(StrategySpec::new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_matches_built_strategies() {
+        let ids = strategy_ids();
+        for (i, entry) in registry().iter().enumerate() {
+            assert!(!ids[..i].contains(&entry.info.id), "Duplicate id {}", entry.info.id);
+
+            let strategy = create_strategy(entry.info.id).unwrap();
+            assert_eq!(strategy.name(), entry.info.name, "Registry name drifted for {}", entry.info.id);
+        }
+    }
+
+    #[test]
+    fn test_parameterised_families_stay_out_of_the_default_field() {
+        let field = field_ids();
+        assert!(field.contains(&"tit_for_tat"));
+        assert!(!field.contains(&"memory_one"));
+        assert_eq!(default_roster().len(), field.len());
+        // still available by name
+        assert!(create_strategy("memory_one").is_ok());
+    }
+
+    #[test]
+    fn test_unknown_id_is_an_error_listing_valid_ids() {
+        let err = create_strategy("tit_for_tatt").err().expect("typo must not fall back silently");
+
+        match &err {
+            EngineError::UnknownStrategy { id, valid_ids } => {
+                assert_eq!(id, "tit_for_tatt");
+                assert!(valid_ids.contains(&"tit_for_tat".to_string()));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "unknown_strategy");
+        assert!(json["message"].as_str().unwrap().contains("tit_for_tat"));
+    }
+
+    #[test]
+    fn test_strategy_spec_shorthand_and_object_forms() {
+        let short: StrategySpec = "generous_tft(forgiveness=0.3)".parse().unwrap();
+        let object: StrategySpec = serde_json
+            ::from_str(r#"{"id": "generous_tft", "params": {"forgiveness": 0.3}}"#)
+            .unwrap();
+        let bare: StrategySpec = serde_json::from_str(r#""generous_tft(forgiveness = 0.3)""#).unwrap();
+
+        assert_eq!(short, object);
+        assert_eq!(short, bare);
+        assert_eq!(short.display_name().unwrap(), "Generous TFT (forgiveness=0.3)");
+        assert_eq!(StrategySpec::new("joss").display_name().unwrap(), "Joss");
+
+        assert!(matches!("joss(sneakiness=0.2".parse::<StrategySpec>(), Err(EngineError::InvalidStrategySpec { .. })));
+        assert!(matches!("joss(sneakiness)".parse::<StrategySpec>(), Err(EngineError::InvalidStrategySpec { .. })));
+    }
+
+    #[test]
+    fn test_specs_can_carry_their_own_state_machine() {
+        let json = r#"{
+            "machine": {
+                "name": "Grim",
+                "initial": "calm",
+                "states": {
+                    "calm": { "action": "Cooperate", "on_cooperate": "calm", "on_defect": "grim" },
+                    "grim": { "action": "Defect", "on_cooperate": "grim", "on_defect": "grim" }
+                }
+            },
+            "label": "Grim FSM"
+        }"#;
+        let spec: StrategySpec = serde_json::from_str(json).unwrap();
+        assert_eq!(spec.id, MACHINE_ID);
+        assert_eq!(spec.display_name().unwrap(), "Grim FSM");
+        assert_eq!(build_strategy(&spec).unwrap().name(), "Grim");
+        assert_eq!(memory_one_of(&spec, &PayoffMatrix { t: 5, r: 3, p: 1, s: 0 }).unwrap(), None);
+
+        let dangling = json.replace(r#""on_defect": "grim" }"#, r#""on_defect": "gone" }"#);
+        assert!(serde_json::from_str::<StrategySpec>(&dangling).is_err());
+    }
+
+    #[test]
+    fn test_build_strategy_rejects_unknown_parameters() {
+        let spec: StrategySpec = "tit_for_tat(forgiveness=0.3)".parse().unwrap();
+
+        match build_strategy(&spec) {
+            Err(EngineError::UnknownParameter { param, valid_params, .. }) => {
+                assert_eq!(param, "forgiveness");
+                assert!(valid_params.is_empty());
+            }
+            _ => panic!("Tit-For-Tat accepted a parameter it does not have"),
+        }
+    }
+
+    #[test]
+    fn test_memory_one_spans_the_classic_strategies() {
+        use crate::Action::{ Cooperate as C, Defect as D };
+        use crate::{ seeded_rng, History, PayoffMatrix, Round };
+
+        let matrix = PayoffMatrix { t: 5, r: 3, p: 1, s: 0 };
+        let mut rng = seeded_rng(Some(1));
+        let histories: [&[Round]; 5] = [&[], &[(C, C)], &[(C, D)], &[(D, C)], &[(D, D)]];
+        for (classic, point) in [
+            ("tit_for_tat", "memory_one"),
+            ("pavlov", "memory_one(p_dc=0,p_dd=1)"),
+            ("always_defect", "memory_one(p_first=0,p_cc=0,p_dc=0)"),
+            ("always_cooperate", "memory_one(p_cd=1,p_dd=1)"),
+        ] {
+            let mut classic = create_strategy(classic).unwrap();
+            let mut point = build_strategy(&point.parse().unwrap()).unwrap();
+            for rounds in histories {
+                assert_eq!(
+                    classic.next_move(History::new(rounds), &matrix, &mut rng),
+                    point.next_move(History::new(rounds), &matrix, &mut rng),
+                    "{} after {:?}",
+                    classic.name(),
+                    rounds
+                );
+            }
+        }
+
+        let out_of_range: StrategySpec = "memory_one(p_cc=1.5)".parse().unwrap();
+        assert!(matches!(build_strategy(&out_of_range), Err(EngineError::InvalidParameter { .. })));
+    }
+}
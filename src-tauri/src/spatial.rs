@@ -1,3 +1,7 @@
+use rand::prelude::*;
+
+use crate::PayoffMatrix;
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum Strategy {
     Cooperate,
@@ -149,6 +153,45 @@ impl<T: Clone> SpatialGrid<T> {
 }
 
 impl SpatialGrid<Strategy> {
+    /// Each cell starts as a cooperator or defector with equal odds.
+    pub fn new_coin_flip(width: usize, height: usize, rng: &mut StdRng) -> Self {
+        Self::new_random(width, height, || {
+            if rng.random::<bool>() { Strategy::Cooperate } else { Strategy::Defect }
+        })
+    }
+
+    /// One generation of the spatial game; noise flips the imitated strategy with that probability.
+    pub fn step(&mut self, payoff_matrix: &PayoffMatrix, noise: f64, rng: &mut StdRng) {
+        let play_match = |p1: &Strategy, p2: &Strategy| -> f32 {
+            match (p1, p2) {
+                (Strategy::Cooperate, Strategy::Cooperate) => payoff_matrix.r as f32,
+                (Strategy::Cooperate, Strategy::Defect) => payoff_matrix.s as f32,
+                (Strategy::Defect, Strategy::Cooperate) => payoff_matrix.t as f32,
+                (Strategy::Defect, Strategy::Defect) => payoff_matrix.p as f32,
+            }
+        };
+
+        let mutate = |strategy: &Strategy| -> Strategy {
+            if rng.random::<f64>() < noise {
+                match strategy {
+                    Strategy::Cooperate => Strategy::Defect,
+                    Strategy::Defect => Strategy::Cooperate,
+                }
+            } else {
+                strategy.clone()
+            }
+        };
+
+        self.next_generation(play_match, mutate);
+    }
+
+    pub fn cooperator_count(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|&s| *s == Strategy::Cooperate)
+            .count()
+    }
+
     pub fn to_byte_array(&self) -> Vec<u8> {
         self.cells
             .iter()
@@ -0,0 +1,643 @@
+use std::cmp::Ordering;
+
+use serde::{ Deserialize, Serialize };
+use rand::prelude::*;
+use rayon::prelude::*;
+
+use crate::markov::{ exact_match, ExactMatch };
+use crate::registry::{ check_strategy, default_roster, memory_one_of };
+use crate::validation::{ check_count, MAX_REPETITIONS };
+use crate::{
+    build_strategy,
+    play_match,
+    stream_rng,
+    Action,
+    EngineError,
+    MatchConfig,
+    MatchResult,
+    PayoffMatrix,
+    StrategySpec,
+};
+
+/// Which side of each pairing gets played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "cli", value(rename_all = "snake_case"))]
+pub enum Pairing {
+    /// Every pair meets twice, once from each seat; each strategy is scored from its own seat.
+    #[default]
+    Ordered,
+    /// Every pair meets once and both players are scored from that one match. The lower roster
+    /// index always takes the first seat, so both seats must play with the same noise.
+    Unordered,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TournamentConfig {
+    /// Independent replays of the whole round-robin, summarised in `TournamentResult::stats`.
+    pub repetitions: u32,
+    /// Whether each entry also plays a copy of itself.
+    pub self_play: bool,
+    pub pairing: Pairing,
+    /// Score pairs of memory-one strategies by their exact expectation instead of playing them;
+    /// their repetitions then agree and their outcomes compare expected scores.
+    pub exact: bool,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        TournamentConfig { repetitions: 1, self_play: true, pairing: Pairing::default(), exact: false }
+    }
+}
+
+impl TournamentConfig {
+    pub fn validate(&self) -> Result<(), EngineError> {
+        check_count("repetitions", self.repetitions as u64, MAX_REPETITIONS as u64)
+    }
+
+    /// Unordered pairings seat entries by roster position, so per-player noise would fall on
+    /// the same strategy of every pair and the results would depend on the roster order.
+    fn check_seats(&self, config: &MatchConfig) -> Result<(), EngineError> {
+        let player = config.player_noise.unwrap_or(config.noise);
+        let opponent = config.opponent_noise.unwrap_or(config.noise);
+        if self.pairing == Pairing::Unordered && player != opponent {
+            return Err(EngineError::InvalidParameter {
+                param: "opponent_noise".to_string(),
+                value: format!("{} against {}", opponent, player),
+                reason: "unordered pairing needs the same execution noise for both seats".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The (i, j) matches of one round-robin over `n` entries, i seated first.
+    fn pairings(&self, n: usize) -> Vec<(usize, usize)> {
+        (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                let seat = match self.pairing {
+                    Pairing::Ordered => true,
+                    Pairing::Unordered => i <= j,
+                };
+                seat && (self.self_play || i != j)
+            })
+            .collect()
+    }
+}
+
+/// One competitor's total score across the repetitions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyStats {
+    pub name: String,
+    pub mean: f64,
+    /// Sample standard deviation; 0 for a single repetition.
+    pub std_dev: f64,
+    /// 95% confidence interval of the mean (Student's t).
+    pub ci95: (f64, f64),
+    /// Median competition rank (tied scores share the better rank).
+    pub median_rank: f64,
+    /// Fraction of repetitions this strategy finished first in, ties included.
+    pub win_rate: f64,
+    /// Mean payoff per round per opponent, comparable across match lengths and roster sizes.
+    pub per_round: f64,
+    /// `per_round` on the matrix's [worst, best] scale; None for a constant matrix.
+    pub normalised: Option<f64>,
+}
+
+/// Match results of one competitor against another, counted over all repetitions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outcomes {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// Pairwise view of the round-robin, both axes in roster order: cell [i][j] describes
+/// how `names[i]` fared against `names[j]`, averaged over the repetitions. The diagonal
+/// stays empty when self-play is off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossTable {
+    pub names: Vec<String>,
+    /// Mean per-round payoff.
+    pub payoffs: Vec<Vec<f64>>,
+    /// Fraction of moves that were cooperative (after noise).
+    pub cooperation: Vec<Vec<f64>>,
+    pub outcomes: Vec<Vec<Outcomes>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TournamentResult {
+    /// Mean total score per competitor, rounded, best first.
+    pub ranking: Vec<(String, i64)>,
+    /// Mean discounted totals in the same order as `ranking`, present when a discount was requested.
+    pub discounted_scores: Option<Vec<f64>>,
+    pub repetitions: u32,
+    /// Spread of the totals, in the same order as `ranking`.
+    pub stats: Vec<StrategyStats>,
+    pub cross_table: CrossTable,
+}
+
+/// What one match contributes to the totals and to the cross-table.
+#[derive(Clone, Copy)]
+struct Sample {
+    score: f64,
+    discounted: f64,
+    per_round: f64,
+    cooperation: f64,
+    outcome: Ordering,
+}
+
+impl Sample {
+    fn first_seat(result: &MatchResult) -> Self {
+        let rounds = result.rounds.len() as f64;
+        let cooperated = result.rounds
+            .iter()
+            .filter(|(mine, _)| *mine == Action::Cooperate)
+            .count();
+        Sample {
+            score: result.player_score as f64,
+            discounted: result.player_discounted_score.unwrap_or(0.0),
+            per_round: (result.player_score as f64) / rounds,
+            cooperation: (cooperated as f64) / rounds,
+            outcome: result.player_score.cmp(&result.opponent_score),
+        }
+    }
+
+    fn second_seat(result: &MatchResult) -> Self {
+        let rounds = result.rounds.len() as f64;
+        let cooperated = result.rounds
+            .iter()
+            .filter(|(_, theirs)| *theirs == Action::Cooperate)
+            .count();
+        Sample {
+            score: result.opponent_score as f64,
+            discounted: result.opponent_discounted_score.unwrap_or(0.0),
+            per_round: (result.opponent_score as f64) / rounds,
+            cooperation: (cooperated as f64) / rounds,
+            outcome: result.opponent_score.cmp(&result.player_score),
+        }
+    }
+
+    fn exact_seats(exact: &ExactMatch) -> (Self, Self) {
+        let first = Sample {
+            score: exact.player_score,
+            discounted: exact.player_discounted_score.unwrap_or(0.0),
+            per_round: exact.player_per_round,
+            cooperation: exact.player_cooperation,
+            outcome: exact.player_score.total_cmp(&exact.opponent_score),
+        };
+        let second = Sample {
+            score: exact.opponent_score,
+            discounted: exact.opponent_discounted_score.unwrap_or(0.0),
+            per_round: exact.opponent_per_round,
+            cooperation: exact.opponent_cooperation,
+            outcome: first.outcome.reverse(),
+        };
+        (first, second)
+    }
+}
+
+/// Display names for a roster; repeated entries get " #2", " #3", ... so rankings stay readable.
+fn roster_names(roster: &[StrategySpec]) -> Result<Vec<String>, EngineError> {
+    let base = roster
+        .iter()
+        .map(|spec| spec.display_name())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(
+        base
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let earlier = base[..i]
+                    .iter()
+                    .filter(|n| *n == name)
+                    .count();
+                if earlier == 0 { name.clone() } else { format!("{} #{}", name, earlier + 1) }
+            })
+            .collect()
+    )
+}
+
+/// Two-sided 97.5% quantile of Student's t with `df` degrees of freedom: tabulated up to 30,
+/// then the Cornish-Fisher expansion around the normal quantile, which is within 1e-4 there.
+fn t_critical_95(df: u32) -> f64 {
+    const Z: f64 = 1.959964;
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.16,
+        2.145, 2.131, 2.12, 2.11, 2.101, 2.093, 2.086, 2.08, 2.074, 2.069, 2.064, 2.06, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => 0.0,
+        1..=30 => TABLE[(df - 1) as usize],
+        _ => {
+            let v = df as f64;
+            let z2 = Z * Z;
+            Z +
+                (Z * (z2 + 1.0)) / (4.0 * v) +
+                (Z * (5.0 * z2 * z2 + 16.0 * z2 + 3.0)) / (96.0 * v * v) +
+                (Z * (3.0 * z2 * z2 * z2 + 19.0 * z2 * z2 + 17.0 * z2 - 15.0)) / (384.0 * v * v * v)
+        }
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] }
+}
+
+fn summarise(
+    name: String,
+    totals: &[f64],
+    ranks: &mut [f64],
+    per_round: f64,
+    matrix: &PayoffMatrix
+) -> StrategyStats {
+    let n = totals.len() as f64;
+    let mean = totals.iter().sum::<f64>() / n;
+    let std_dev = if totals.len() > 1 {
+        (
+            totals
+                .iter()
+                .map(|x| (x - mean).powi(2))
+                .sum::<f64>() /
+            (n - 1.0)
+        ).sqrt()
+    } else {
+        0.0
+    };
+    let half_width = (t_critical_95(totals.len() as u32 - 1) * std_dev) / n.sqrt();
+    let wins = ranks
+        .iter()
+        .filter(|&&r| r == 1.0)
+        .count();
+    StrategyStats {
+        name,
+        mean,
+        std_dev,
+        ci95: (mean - half_width, mean + half_width),
+        median_rank: median(ranks),
+        win_rate: (wins as f64) / n,
+        per_round,
+        normalised: matrix.normalise(per_round),
+    }
+}
+
+/// Round-robin over `roster` (the default field when empty), paired as
+/// `tournament` asks. Duplicates are separate competitors.
+/// Matches run in parallel, each on its own stream split off `rng`, so seeded results do not
+/// depend on the number of threads.
+pub fn run_tournament(
+    roster: &[StrategySpec],
+    config: MatchConfig,
+    tournament: TournamentConfig,
+    rng: &mut StdRng
+) -> Result<TournamentResult, EngineError> {
+    config.validate()?;
+    tournament.validate()?;
+    tournament.check_seats(&config)?;
+    let roster = if roster.is_empty() { default_roster() } else { roster.to_vec() };
+    for spec in &roster {
+        check_strategy(spec, &config.payoff_matrix)?;
+    }
+    let names = roster_names(&roster)?;
+    let n = roster.len();
+    if !tournament.self_play && n < 2 {
+        return Err(EngineError::InvalidParameter {
+            param: "roster".to_string(),
+            value: n.to_string(),
+            reason: "needs at least two entries without self-play".to_string(),
+        });
+    }
+    let memory_one = roster
+        .iter()
+        .map(|spec| memory_one_of(spec, &config.payoff_matrix))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pairings = tournament.pairings(n);
+    // an exact pairing comes out the same in every repetition, so it is worked out once
+    let exact: Vec<Option<(Sample, Sample)>> = pairings
+        .par_iter()
+        .map(|&(i, j)| {
+            match (tournament.exact, &memory_one[i], &memory_one[j]) {
+                (true, Some(m1), Some(m2)) => Some(Sample::exact_seats(&exact_match(m1, m2, config))),
+                _ => None,
+            }
+        })
+        .collect();
+    let reps = tournament.repetitions as usize;
+    let base: u64 = rng.random();
+    let results = (0..reps * pairings.len())
+        .into_par_iter()
+        .map(|stream| {
+            let (i, j) = pairings[stream % pairings.len()];
+            if let Some(samples) = exact[stream % pairings.len()] {
+                return Ok(samples);
+            }
+            let mut rng = stream_rng(base, stream as u64);
+            let mut p1 = build_strategy(&roster[i])?;
+            let mut p2 = build_strategy(&roster[j])?;
+            let result = play_match(p1.as_mut(), p2.as_mut(), config, &mut rng);
+            Ok((Sample::first_seat(&result), Sample::second_seat(&result)))
+        })
+        .collect::<Result<Vec<_>, EngineError>>()?;
+
+    // summed in stream order so the floating-point totals are reproducible too
+    let mut totals = vec![vec![0.0; reps]; n];
+    let mut discounted_totals = vec![0.0; n];
+    let mut cross_table = CrossTable {
+        names: names.clone(),
+        payoffs: vec![vec![0.0; n]; n],
+        cooperation: vec![vec![0.0; n]; n],
+        outcomes: vec![vec![Outcomes::default(); n]; n],
+    };
+    let mut record = |rep: usize, i: usize, j: usize, sample: Sample| {
+        totals[i][rep] += sample.score;
+        discounted_totals[i] += sample.discounted / (reps as f64);
+        cross_table.payoffs[i][j] += sample.per_round / (reps as f64);
+        cross_table.cooperation[i][j] += sample.cooperation / (reps as f64);
+        let outcomes = &mut cross_table.outcomes[i][j];
+        match sample.outcome {
+            Ordering::Greater => {
+                outcomes.wins += 1;
+            }
+            Ordering::Less => {
+                outcomes.losses += 1;
+            }
+            Ordering::Equal => {
+                outcomes.draws += 1;
+            }
+        }
+    };
+    for (stream, (first, second)) in results.into_iter().enumerate() {
+        let rep = stream / pairings.len();
+        let (i, j) = pairings[stream % pairings.len()];
+        record(rep, i, j, first);
+        if tournament.pairing == Pairing::Unordered && i != j {
+            record(rep, j, i, second);
+        }
+    }
+
+    let mut ranks = vec![vec![0.0; reps]; n];
+    for rep in 0..reps {
+        for i in 0..n {
+            let better = (0..n)
+                .filter(|&k| totals[k][rep] > totals[i][rep])
+                .count();
+            ranks[i][rep] = (better + 1) as f64;
+        }
+    }
+
+    let opponents = if tournament.self_play { n } else { n - 1 };
+    let mut entries: Vec<(StrategyStats, f64)> = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let per_round = cross_table.payoffs[i].iter().sum::<f64>() / (opponents as f64);
+            let stats = summarise(name, &totals[i], &mut ranks[i], per_round, &config.payoff_matrix);
+            (stats, discounted_totals[i])
+        })
+        .collect();
+    entries.sort_by(|a, b| b.0.mean.total_cmp(&a.0.mean));
+
+    let discounted_scores = config.discount.map(|_| entries.iter().map(|e| e.1).collect());
+    let ranking = entries
+        .iter()
+        .map(|(s, _)| (s.name.clone(), s.mean.round() as i64))
+        .collect();
+    let stats = entries
+        .into_iter()
+        .map(|(s, _)| s)
+        .collect();
+    Ok(TournamentResult {
+        ranking,
+        discounted_scores,
+        repetitions: tournament.repetitions,
+        stats,
+        cross_table,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ seeded_rng, test_config };
+
+    #[test]
+    fn test_custom_roster_with_duplicates_and_variants() {
+        let roster: Vec<StrategySpec> = [
+            "tit_for_tat",
+            "always_defect",
+            "always_defect",
+            "generous_tft(forgiveness=1)",
+        ]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        let result = run_tournament(&roster, test_config(10), TournamentConfig::default(), &mut seeded_rng(Some(1))).unwrap();
+
+        let names: Vec<&str> = result.ranking
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .collect();
+        assert_eq!(result.ranking.len(), 4);
+        assert!(names.contains(&"Always Defect"));
+        assert!(names.contains(&"Always Defect #2"));
+        assert!(names.contains(&"Generous TFT (forgiveness=1)"));
+
+        // TFT: 10 rounds vs itself (30), 1 sucker + 9 punishments vs each ALLD (9 twice), 30 vs GTFT
+        let tft = result.ranking.iter().find(|(n, _)| n == "Tit-For-Tat").unwrap();
+        assert_eq!(tft.1, 30 + 9 + 9 + 30);
+
+        // per round per opponent: (3 + 0.9 + 0.9 + 3) / 4, i.e. 0.39 of the way from S=0 to T=5
+        let tft_stats = result.stats.iter().find(|s| s.name == "Tit-For-Tat").unwrap();
+        assert!((tft_stats.per_round - 1.95).abs() < 1e-12);
+        assert!((tft_stats.normalised.unwrap() - 0.39).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_empty_roster_means_the_default_field() {
+        let result = run_tournament(&[], test_config(10), TournamentConfig::default(), &mut seeded_rng(Some(1))).unwrap();
+        assert_eq!(result.ranking.len(), crate::field_ids().len());
+    }
+
+    #[test]
+    fn test_bad_roster_entry_is_reported() {
+        let roster = vec![StrategySpec::new("tit_for_tat"), StrategySpec::new("nope")];
+        assert!(run_tournament(&roster, test_config(10), TournamentConfig::default(), &mut seeded_rng(Some(1))).is_err());
+    }
+
+    #[test]
+    fn test_seeded_results_do_not_depend_on_thread_count() {
+        let noisy = MatchConfig { noise: 0.1, discount: Some(0.95), ..test_config(10) };
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| run_tournament(&[], noisy, TournamentConfig { repetitions: 3, ..Default::default() }, &mut seeded_rng(Some(9))).unwrap())
+        };
+
+        let (single, many) = (run(1), run(4));
+        assert_eq!(single.ranking, many.ranking);
+        assert_eq!(single.discounted_scores, many.discounted_scores);
+    }
+
+    #[test]
+    fn test_repetitions_summarise_spread_and_ranks() {
+        let noisy = MatchConfig { noise: 0.05, ..test_config(10) };
+        let result = run_tournament(
+            &[],
+            noisy,
+            TournamentConfig { repetitions: 20, ..Default::default() },
+            &mut seeded_rng(Some(3))
+        ).unwrap();
+
+        assert_eq!(result.stats.len(), result.ranking.len());
+        let total_wins: f64 = result.stats
+            .iter()
+            .map(|s| s.win_rate)
+            .sum();
+        assert!(total_wins >= 1.0 - 1e-9);
+        for (stats, (name, score)) in result.stats.iter().zip(&result.ranking) {
+            assert_eq!(&stats.name, name);
+            assert_eq!(stats.mean.round() as i64, *score);
+            assert!(stats.ci95.0 <= stats.mean && stats.mean <= stats.ci95.1);
+            assert!(stats.median_rank >= 1.0);
+        }
+
+        // without noise or stochastic strategies there is no spread at all
+        let roster = vec![StrategySpec::new("tit_for_tat"), StrategySpec::new("always_defect")];
+        let exact = run_tournament(
+            &roster,
+            test_config(10),
+            TournamentConfig { repetitions: 5, ..Default::default() },
+            &mut seeded_rng(Some(3))
+        ).unwrap();
+        let alld = exact.stats.iter().find(|s| s.name == "Always Defect").unwrap();
+        assert_eq!(alld.std_dev, 0.0);
+        assert_eq!(alld.ci95, (alld.mean, alld.mean));
+    }
+
+    #[test]
+    fn test_cross_table_shows_where_points_come_from() {
+        let roster = vec![StrategySpec::new("tit_for_tat"), StrategySpec::new("always_defect")];
+        let result = run_tournament(
+            &roster,
+            test_config(10),
+            TournamentConfig { repetitions: 2, ..Default::default() },
+            &mut seeded_rng(Some(1))
+        ).unwrap();
+        let table = &result.cross_table;
+
+        assert_eq!(table.names, ["Tit-For-Tat", "Always Defect"]);
+        assert_eq!(table.payoffs[0], [3.0, 0.9]);
+        assert_eq!(table.payoffs[1], [1.4, 1.0]);
+        assert_eq!(table.cooperation[0], [1.0, 0.1]);
+        assert_eq!(table.cooperation[1], [0.0, 0.0]);
+        assert_eq!(table.outcomes[0][1], Outcomes { wins: 0, losses: 2, draws: 0 });
+        assert_eq!(table.outcomes[1][0], Outcomes { wins: 2, losses: 0, draws: 0 });
+        assert_eq!(table.outcomes[0][0].draws, 2);
+    }
+
+    #[test]
+    fn test_unordered_pairing_without_self_play_scores_both_seats() {
+        let roster = vec![StrategySpec::new("tit_for_tat"), StrategySpec::new("always_defect")];
+        let tournament = TournamentConfig {
+            repetitions: 2,
+            self_play: false,
+            pairing: Pairing::Unordered,
+            ..Default::default()
+        };
+        let result = run_tournament(&roster, test_config(10), tournament, &mut seeded_rng(Some(1))).unwrap();
+        let table = &result.cross_table;
+
+        assert_eq!(result.ranking, [("Always Defect".to_string(), 14), ("Tit-For-Tat".to_string(), 9)]);
+        assert_eq!(table.payoffs[0], [0.0, 0.9]);
+        assert_eq!(table.payoffs[1], [1.4, 0.0]);
+        assert_eq!(table.outcomes[0][1], Outcomes { wins: 0, losses: 2, draws: 0 });
+        assert_eq!(table.outcomes[1][0], Outcomes { wins: 2, losses: 0, draws: 0 });
+        assert_eq!(table.outcomes[0][0], Outcomes::default());
+
+        // one opponent, so per round is just the head-to-head payoff
+        let tft = result.stats.iter().find(|s| s.name == "Tit-For-Tat").unwrap();
+        assert!((tft.per_round - 0.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unordered_scores_do_not_depend_on_roster_order() {
+        let roster: Vec<StrategySpec> = ["tit_for_tat", "pavlov", "always_defect", "generous_tft"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let reversed: Vec<StrategySpec> = roster.iter().rev().cloned().collect();
+        let tournament = TournamentConfig { pairing: Pairing::Unordered, exact: true, ..Default::default() };
+        let noisy = MatchConfig { noise: 0.05, ..test_config(10) };
+
+        let forward = run_tournament(&roster, noisy, tournament, &mut seeded_rng(Some(1))).unwrap();
+        let backward = run_tournament(&reversed, noisy, tournament, &mut seeded_rng(Some(2))).unwrap();
+        for stats in &forward.stats {
+            let other = backward.stats.iter().find(|s| s.name == stats.name).unwrap();
+            assert!((stats.mean - other.mean).abs() < 1e-9, "{}", stats.name);
+        }
+
+        let one_sided = MatchConfig { player_noise: Some(0.1), ..noisy };
+        match run_tournament(&roster, one_sided, tournament, &mut seeded_rng(Some(1))) {
+            Err(EngineError::InvalidParameter { param, .. }) => assert_eq!(param, "opponent_noise"),
+            other => panic!("one-sided noise was accepted: {:?}", other.map(|r| r.ranking)),
+        }
+        let ordered = TournamentConfig { pairing: Pairing::Ordered, ..tournament };
+        assert!(run_tournament(&roster, one_sided, ordered, &mut seeded_rng(Some(1))).is_ok());
+    }
+
+    #[test]
+    fn test_t_quantiles_past_the_table_approach_the_normal_one() {
+        for (df, t) in [(31, 2.04), (40, 2.021), (60, 2.0), (120, 1.98), (1000, 1.962)] {
+            assert!((t_critical_95(df) - t).abs() < 1e-3, "df {}: {}", df, t_critical_95(df));
+        }
+        assert!(t_critical_95(30) > t_critical_95(31));
+    }
+
+    #[test]
+    fn test_pairings_follow_the_options() {
+        let ordered = TournamentConfig::default();
+        let unordered = TournamentConfig { pairing: Pairing::Unordered, ..ordered };
+        assert_eq!(ordered.pairings(3).len(), 9);
+        assert_eq!(TournamentConfig { self_play: false, ..ordered }.pairings(3).len(), 6);
+        assert_eq!(unordered.pairings(3), [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2), (2, 2)]);
+        assert_eq!(TournamentConfig { self_play: false, ..unordered }.pairings(3), [(0, 1), (0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_lone_entry_needs_self_play() {
+        let roster = vec![StrategySpec::new("tit_for_tat")];
+        let tournament = TournamentConfig { self_play: false, ..Default::default() };
+        assert!(run_tournament(&roster, test_config(10), tournament, &mut seeded_rng(Some(1))).is_err());
+    }
+
+    #[test]
+    fn test_exact_scoring_agrees_with_play_and_removes_sampling_spread() {
+        let roster: Vec<StrategySpec> = ["tit_for_tat", "always_defect", "grim_trigger", "pavlov"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let exact = TournamentConfig { exact: true, ..Default::default() };
+
+        let played = run_tournament(&roster, test_config(10), TournamentConfig::default(), &mut seeded_rng(Some(1))).unwrap();
+        let computed = run_tournament(&roster, test_config(10), exact, &mut seeded_rng(Some(1))).unwrap();
+        assert_eq!(played.ranking, computed.ranking);
+
+        let noisy = MatchConfig { noise: 0.1, ..test_config(10) };
+        let roster = vec![StrategySpec::new("generous_tft"), StrategySpec::new("joss")];
+        let result = run_tournament(
+            &roster,
+            noisy,
+            TournamentConfig { repetitions: 10, ..exact },
+            &mut seeded_rng(Some(1))
+        ).unwrap();
+        assert!(result.stats.iter().all(|s| s.std_dev < 1e-9));
+    }
+}
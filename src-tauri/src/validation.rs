@@ -0,0 +1,200 @@
+use serde::Serialize;
+
+use crate::{ EngineError, MatchConfig, NoiseSchedule, PayoffMatrix };
+
+/// Longest match accepted from the outside; every round is kept in the match history, so this
+/// bounds the memory and time one tournament can take.
+pub const MAX_ROUNDS: u32 = 100_000;
+pub const MAX_GENERATIONS: u32 = 100_000;
+pub const MAX_GRID_SIDE: usize = 1_000;
+pub const MAX_POPULATION: u32 = 10_000;
+pub const MAX_REPETITIONS: u32 = 100_000;
+pub const MAX_MORAN_STEPS: u64 = 100_000_000;
+/// Bound on |T|, |R|, |P|, |S| so accumulated scores cannot overflow.
+pub const MAX_PAYOFF: i32 = 1_000;
+
+fn invalid(param: &str, value: impl ToString, reason: impl ToString) -> EngineError {
+    EngineError::InvalidParameter {
+        param: param.to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Probabilities must lie in [0, 1]; NaN is rejected too.
+pub fn check_probability(param: &str, value: f64) -> Result<(), EngineError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(param, value, "must be a probability between 0 and 1"))
+    }
+}
+
+/// Real parameters within inclusive bounds; NaN is rejected too.
+pub fn check_range(param: &str, value: f64, (min, max): (f64, f64)) -> Result<(), EngineError> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(param, value, format!("must be between {} and {}", min, max)))
+    }
+}
+
+/// Integer parameters counted from 1 up to an inclusive maximum.
+pub fn check_count(param: &str, value: u64, max: u64) -> Result<(), EngineError> {
+    if (1..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(param, value, format!("must be between 1 and {}", max)))
+    }
+}
+
+/// Initial counts must describe at least one individual and at most `MAX_POPULATION`, since
+/// Wright-Fisher and Moran steps draw per individual.
+pub fn check_population(counts: &[u32]) -> Result<(), EngineError> {
+    let total: u64 = counts
+        .iter()
+        .map(|&n| n as u64)
+        .sum();
+    check_count("initial_populations", total, MAX_POPULATION as u64)
+}
+
+pub fn check_grid_size(width: usize, height: usize) -> Result<(), EngineError> {
+    check_count("width", width as u64, MAX_GRID_SIDE as u64)?;
+    check_count("height", height as u64, MAX_GRID_SIDE as u64)
+}
+
+/// A non-PD matrix is still a legal game (Snowdrift, Stag Hunt...); it is only surfaced as a warning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PayoffWarning {
+    pub kind: &'static str,
+    pub message: String,
+}
+
+impl PayoffMatrix {
+    pub fn validate(&self) -> Result<(), EngineError> {
+        for (name, value) in [("t", self.t), ("r", self.r), ("p", self.p), ("s", self.s)] {
+            if value.abs() > MAX_PAYOFF {
+                return Err(
+                    invalid(
+                        &format!("payoff_matrix.{}", name),
+                        value,
+                        format!("must be between -{} and {}", MAX_PAYOFF, MAX_PAYOFF)
+                    )
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Ways in which the matrix fails to be an iterated Prisoner's Dilemma; empty when it is one.
+    pub fn dilemma_warnings(&self) -> Vec<PayoffWarning> {
+        let PayoffMatrix { t, r, p, s } = *self;
+        let mut warnings = Vec::new();
+        if !(t > r && r > p && p > s) {
+            warnings.push(PayoffWarning {
+                kind: "payoff_order",
+                message: format!(
+                    "not a Prisoner's Dilemma: T > R > P > S does not hold (T={}, R={}, P={}, S={})",
+                    t,
+                    r,
+                    p,
+                    s
+                ),
+            });
+        }
+        if 2 * r <= t + s {
+            warnings.push(PayoffWarning {
+                kind: "alternation_pays",
+                message: format!(
+                    "2R > T + S does not hold ({} <= {}): taking turns exploiting each other pays at least as well as cooperating",
+                    2 * r,
+                    t + s
+                ),
+            });
+        }
+        warnings
+    }
+}
+
+impl MatchConfig {
+    /// Rejects values the engine cannot run with, such as noise outside [0, 1] or zero rounds.
+    pub fn validate(&self) -> Result<(), EngineError> {
+        check_count("rounds", self.rounds as u64, MAX_ROUNDS as u64)?;
+        check_probability("noise", self.noise)?;
+        for (param, noise) in [("player_noise", self.player_noise), ("opponent_noise", self.opponent_noise)] {
+            if let Some(noise) = noise {
+                check_probability(param, noise)?;
+            }
+        }
+        self.validate_noise_schedule()?;
+        check_probability("perception_noise", self.perception_noise)?;
+        if let Some(w) = self.continuation {
+            check_probability("continuation", w)?;
+        }
+        if let Some(d) = self.discount {
+            check_probability("discount", d)?;
+        }
+        self.payoff_matrix.validate()
+    }
+
+    fn validate_noise_schedule(&self) -> Result<(), EngineError> {
+        match self.noise_schedule {
+            NoiseSchedule::Constant => Ok(()),
+            NoiseSchedule::Ramp { start, end, to } => {
+                if end <= start {
+                    return Err(invalid("noise_schedule.end", end, format!("must come after start ({})", start)));
+                }
+                check_probability("noise_schedule.to", to)
+            }
+            NoiseSchedule::Shock { rate, every, .. } => {
+                if every == Some(0) {
+                    return Err(invalid("noise_schedule.every", 0, "must be positive"));
+                }
+                check_probability("noise_schedule.rate", rate)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_config;
+
+    #[test]
+    fn test_match_config_rejects_impossible_values() {
+        assert!(test_config(10).validate().is_ok());
+
+        let cases = [
+            MatchConfig { noise: 1.5, ..test_config(10) },
+            MatchConfig { noise: f64::NAN, ..test_config(10) },
+            MatchConfig { perception_noise: -0.5, ..test_config(10) },
+            MatchConfig { opponent_noise: Some(2.0), ..test_config(10) },
+            MatchConfig { noise_schedule: NoiseSchedule::Ramp { start: 10, end: 10, to: 0.1 }, ..test_config(10) },
+            MatchConfig { noise_schedule: NoiseSchedule::Shock { start: 0, length: 5, rate: 0.5, every: Some(0) }, ..test_config(10) },
+            MatchConfig { rounds: 0, ..test_config(10) },
+            MatchConfig { rounds: MAX_ROUNDS + 1, ..test_config(10) },
+            MatchConfig { continuation: Some(-0.1), ..test_config(10) },
+            MatchConfig { payoff_matrix: PayoffMatrix { t: i32::MAX, r: 3, p: 1, s: 0 }, ..test_config(10) },
+        ];
+        for case in cases {
+            match case.validate() {
+                Err(EngineError::InvalidParameter { .. }) => {}
+                other => panic!("{:?} was not rejected: {:?}", case, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_dilemma_warnings() {
+        assert!(PayoffMatrix { t: 5, r: 3, p: 1, s: 0 }.dilemma_warnings().is_empty());
+
+        let stag_hunt = PayoffMatrix { t: 3, r: 5, p: 1, s: 0 }.dilemma_warnings();
+        assert_eq!(stag_hunt.len(), 1);
+        assert_eq!(stag_hunt[0].kind, "payoff_order");
+
+        let alternating = PayoffMatrix { t: 10, r: 3, p: 1, s: 0 }.dilemma_warnings();
+        assert_eq!(alternating.len(), 1);
+        assert_eq!(alternating[0].kind, "alternation_pays");
+    }
+}
@@ -0,0 +1,242 @@
+use rand::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+use crate::{ Action, EngineError, History, MemoryOne, PayoffMatrix, Strategy };
+
+/// A Press–Dyson zero-determinant strategy, which unilaterally enforces
+/// `my_payoff - l = slope * (opponent_payoff - l)` on long-run per-round payoffs.
+/// The baseline l is given as a position between P (0, extortion) and R (1, generosity),
+/// and `scale` as a fraction of the largest feasible φ; both keep the parameters independent
+/// of the payoff matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ZeroDeterminant {
+    /// χ ≥ 1: how many times the opponent's surplus over the baseline this player takes.
+    pub slope: f64,
+    pub baseline: f64,
+    /// How quickly payoffs settle onto the enforced line; 1 is as fast as feasible.
+    pub scale: f64,
+}
+
+impl ZeroDeterminant {
+    /// Extortion with factor χ: the baseline is P, so the opponent can only raise its own
+    /// payoff above mutual defection by raising this player's χ times as much.
+    pub fn extortionate(factor: f64) -> Self {
+        ZeroDeterminant { slope: factor, baseline: 0.0, scale: 1.0 }
+    }
+
+    /// The generous counterpart: the baseline is R, and this player gives up more than the
+    /// opponent whenever mutual cooperation breaks down.
+    pub fn generous(slope: f64) -> Self {
+        ZeroDeterminant { slope, baseline: 1.0, scale: 1.0 }
+    }
+
+    /// Cooperation probabilities after CC, CD, DC and DD before any clamping:
+    /// p~ = φ [(S_X - l) - χ (S_Y - l)], with p~ = (p_cc - 1, p_cd - 1, p_dc, p_dd).
+    fn raw(&self, matrix: &PayoffMatrix) -> MemoryOne {
+        let [r, s, t, p] = [matrix.r, matrix.s, matrix.t, matrix.p].map(|x| x as f64);
+        let l = p + self.baseline * (r - p);
+        let chi = self.slope;
+        let surplus = [
+            (r - l) - chi * (r - l),
+            (s - l) - chi * (t - l),
+            (t - l) - chi * (s - l),
+            (p - l) - chi * (p - l),
+        ];
+        let largest = surplus.iter().fold(0.0_f64, |m, a| m.max(a.abs()));
+        let phi = if largest > 0.0 { self.scale / largest } else { 0.0 };
+        MemoryOne {
+            p_first: 1.0,
+            p_cc: 1.0 + phi * surplus[0],
+            p_cd: 1.0 + phi * surplus[1],
+            p_dc: phi * surplus[2],
+            p_dd: phi * surplus[3],
+        }
+    }
+
+    /// Which input to blame when `raw` is not a strategy: the baseline if another one would do
+    /// with this slope, the slope if none would, and the matrix if it admits no slope ≥ 1.
+    fn culprit(&self, matrix: &PayoffMatrix) -> &'static str {
+        let [r, s, t, p] = [matrix.r, matrix.s, matrix.t, matrix.p].map(|x| x as f64);
+        let chi = self.slope;
+        if chi < 1.0 {
+            return "slope";
+        }
+        if s > t {
+            return "payoff_matrix";
+        }
+        // with χ > 1 each probability bounds l from one side: p_dd and p_dc from below,
+        // p_cc and p_cd from above
+        let lower = p.max((chi * s - t) / (chi - 1.0));
+        let upper = r.min((chi * t - s) / (chi - 1.0));
+        if lower <= upper + 1e-9 { "baseline" } else { "slope" }
+    }
+
+    /// The memory-one strategy these parameters describe under `matrix`, or an error naming
+    /// the input that puts a probability outside [0, 1]. Always feasible for a Prisoner's
+    /// Dilemma with slope ≥ 1 and scale > 0.
+    pub fn memory_one(&self, matrix: &PayoffMatrix) -> Result<MemoryOne, EngineError> {
+        // φ = 0 would repeat the last move whatever happened: not a zero-determinant strategy
+        if self.scale.is_nan() || self.scale <= 0.0 {
+            return Err(EngineError::InvalidParameter {
+                param: "scale".to_string(),
+                value: self.scale.to_string(),
+                reason: "must be above 0".to_string(),
+            });
+        }
+        let m = self.raw(matrix);
+        let probabilities = [("p_cc", m.p_cc), ("p_cd", m.p_cd), ("p_dc", m.p_dc), ("p_dd", m.p_dd)];
+        for (name, p) in probabilities {
+            if !(-1e-9..=1.0 + 1e-9).contains(&p) {
+                let param = self.culprit(matrix);
+                let value = match param {
+                    "slope" => self.slope.to_string(),
+                    "baseline" => self.baseline.to_string(),
+                    _ => format!("{},{},{},{}", matrix.t, matrix.r, matrix.p, matrix.s),
+                };
+                return Err(EngineError::InvalidParameter {
+                    param: param.to_string(),
+                    value,
+                    reason: format!(
+                        "with slope {} and baseline {} this payoff matrix needs {} = {:.3}, which is not a probability",
+                        self.slope,
+                        self.baseline,
+                        name,
+                        p
+                    ),
+                });
+            }
+        }
+        Ok(self.clamped(matrix))
+    }
+
+    /// `memory_one`, with infeasible probabilities clamped into [0, 1] instead of rejected;
+    /// the enforced relation then no longer holds exactly.
+    pub fn clamped(&self, matrix: &PayoffMatrix) -> MemoryOne {
+        let m = self.raw(matrix);
+        let clamp = |p: f64| p.clamp(0.0, 1.0);
+        MemoryOne {
+            p_first: m.p_first,
+            p_cc: clamp(m.p_cc),
+            p_cd: clamp(m.p_cd),
+            p_dc: clamp(m.p_dc),
+            p_dd: clamp(m.p_dd),
+        }
+    }
+}
+
+/// Plays a `ZeroDeterminant`, working its probabilities out once per match from the match's
+/// matrix. Infeasible parameters are clamped here; `check_strategy` rejects them up front.
+pub struct ZeroDeterminantStrategy {
+    params: ZeroDeterminant,
+    probabilities: Option<MemoryOne>,
+}
+
+impl ZeroDeterminantStrategy {
+    pub fn new(params: ZeroDeterminant) -> Self {
+        ZeroDeterminantStrategy { params, probabilities: None }
+    }
+}
+
+impl Strategy for ZeroDeterminantStrategy {
+    fn name(&self) -> String {
+        "Zero-Determinant".to_string()
+    }
+    fn next_move(
+        &mut self,
+        history: History<'_>,
+        matrix: &PayoffMatrix,
+        rng: &mut StdRng
+    ) -> Action {
+        if history.is_empty() {
+            self.probabilities = None;
+        }
+        let params = self.params;
+        let probabilities = self.probabilities.get_or_insert_with(|| params.clamped(matrix));
+        let p = probabilities.cooperation_probability(history.last());
+        if rng.random_bool(p) { Action::Cooperate } else { Action::Defect }
+    }
+    fn reset(&mut self) {
+        self.probabilities = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markov::exact_match;
+    use crate::{ play_match, run_tournament, seeded_rng, test_config, MatchConfig };
+
+    const PD: PayoffMatrix = PayoffMatrix { t: 5, r: 3, p: 1, s: 0 };
+
+    #[test]
+    fn test_extortioner_enforces_its_line_against_any_opponent() {
+        let zd = ZeroDeterminant { scale: 0.5, ..ZeroDeterminant::extortionate(3.0) };
+        let extortioner = zd.memory_one(&PD).unwrap();
+        assert_eq!(extortioner.p_dd, 0.0);
+
+        let opponents = [
+            MemoryOne { p_first: 0.5, p_cc: 0.9, p_cd: 0.2, p_dc: 0.6, p_dd: 0.4 },
+            MemoryOne { p_first: 1.0, p_cc: 0.99, p_cd: 0.5, p_dc: 0.9, p_dd: 0.7 },
+        ];
+        for opponent in opponents {
+            let (x, y) = exact_match(&extortioner, &opponent, test_config(10)).long_run_payoffs.unwrap();
+            assert!((x - 1.0 - 3.0 * (y - 1.0)).abs() < 1e-9, "{} vs {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_generous_zd_cooperates_after_mutual_cooperation() {
+        let generous = ZeroDeterminant::generous(2.0).memory_one(&PD).unwrap();
+        assert!((generous.p_cc - 1.0).abs() < 1e-12);
+        assert!(generous.p_dd > 0.0);
+    }
+
+    #[test]
+    fn test_infeasible_parameters_are_rejected_but_still_playable() {
+        // Harmony: S > T, so after exploiting a cooperator the extortioner would need p_dc < 0
+        let harmony = PayoffMatrix { t: 1, r: 5, p: 0, s: 3 };
+        let zd = ZeroDeterminant::extortionate(3.0);
+
+        assert!(matches!(zd.memory_one(&harmony), Err(EngineError::InvalidParameter { .. })));
+        let clamped = zd.clamped(&harmony);
+        assert!([clamped.p_cc, clamped.p_cd, clamped.p_dc, clamped.p_dd].iter().all(|p| (0.0..=1.0).contains(p)));
+
+        let roster = ["zero_determinant".parse().unwrap(), "tit_for_tat".parse().unwrap()];
+        let in_harmony = MatchConfig { payoff_matrix: harmony, ..test_config(10) };
+        let tournament = run_tournament(&roster, in_harmony, Default::default(), &mut seeded_rng(Some(1)));
+        assert!(matches!(tournament, Err(EngineError::InvalidParameter { .. })));
+        assert!(run_tournament(&roster, test_config(10), Default::default(), &mut seeded_rng(Some(1))).is_ok());
+    }
+
+    #[test]
+    fn test_errors_name_the_parameter_to_change() {
+        let blamed = |zd: ZeroDeterminant, matrix: PayoffMatrix| match zd.memory_one(&matrix) {
+            Err(EngineError::InvalidParameter { param, .. }) => param,
+            other => panic!("{:?} was accepted: {:?}", zd, other),
+        };
+        // T > S > R: a gentle slope works with a high enough baseline, a steep one with none
+        let matrix = PayoffMatrix { t: 5, r: 3, p: 0, s: 4 };
+        assert_eq!(blamed(ZeroDeterminant::extortionate(1.5), matrix), "baseline");
+        assert!(ZeroDeterminant::generous(1.5).memory_one(&matrix).is_ok());
+        assert_eq!(blamed(ZeroDeterminant::generous(3.0), matrix), "slope");
+
+        let harmony = PayoffMatrix { t: 1, r: 5, p: 0, s: 3 };
+        assert_eq!(blamed(ZeroDeterminant::extortionate(3.0), harmony), "payoff_matrix");
+        assert_eq!(blamed(ZeroDeterminant { scale: 0.0, ..ZeroDeterminant::extortionate(3.0) }, PD), "scale");
+    }
+
+    #[test]
+    fn test_one_instance_follows_the_matrix_of_each_match() {
+        let params = ZeroDeterminant::extortionate(2.0);
+        let mut zd = ZeroDeterminantStrategy::new(params);
+        let mut opponent = crate::Random { cooperation_probability: 0.7 };
+
+        for matrix in [PD, PayoffMatrix { t: 7, r: 4, p: 1, s: 0 }, PD] {
+            let cfg = MatchConfig { payoff_matrix: matrix, ..test_config(10) };
+            let mut fixed = params.memory_one(&matrix).unwrap();
+            let played = play_match(&mut zd, &mut opponent, cfg, &mut seeded_rng(Some(4)));
+            let expected = play_match(&mut fixed, &mut opponent, cfg, &mut seeded_rng(Some(4)));
+            assert_eq!(played.rounds, expected.rounds);
+        }
+    }
+}
@@ -1,12 +1,13 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { MatchResult } from "./types";
+import { MatchResult, errorMessage } from "./types";
 import { GameGrid } from "./GameGrid";
 import { StrategySelector } from "./StrategySelector";
-import { STRATEGIES } from "./strategies";
+import { chartColors, useStrategies } from "./strategies";
 import { SettingsPanel } from "./SettingsPanel";
 import { EvolutionChart } from "./EvolutionChart";
 import { TournamentChart } from "./TournamentChart";
+import { CrossTable, CrossTableHeatmap } from "./CrossTableHeatmap";
 import { Tooltip } from "./Tooltip";
 import { TerminalLine } from "./TerminalLine";
 import { DominanceChart } from "./DominanceChart";
@@ -20,10 +21,14 @@ function App() {
   const [matchData, setMatchData] = useState<MatchResult | null>(null);
   const [evolutionData, setEvolutionData] = useState<any[]>([]);
   const [tournamentData, setTournamentData] = useState<{ name: string, score: number }[]>([]);
+  const [crossTable, setCrossTable] = useState<CrossTable | null>(null);
 
   // --- State for Strategy Selection ---
-  const [p1Strategy, setP1Strategy] = useState(STRATEGIES[0].id);
-  const [p2Strategy, setP2Strategy] = useState(STRATEGIES[1].id);
+  const strategies = useStrategies();
+  // evolution takes one initial count per strategy of the default field
+  const field = strategies.filter(s => s.default_field);
+  const [p1Strategy, setP1Strategy] = useState("tit_for_tat");
+  const [p2Strategy, setP2Strategy] = useState("always_defect");
   const [spatialTrigger, setSpatialTrigger] = useState(0);
   const [speed, setSpeed] = useStickyState(100, "evolutio-speed");
   const [rounds, setRounds] = useStickyState(20, "evolutio-rounds");
@@ -31,9 +36,8 @@ function App() {
   const [noise, setNoise] = useStickyState(0, "evolutio-noise");
   const [payoff, setPayoff] = useStickyState({ t: 5, r: 3, p: 1, s: 0 }, "evolutio-payoff");
 
-  const [initialPops, setInitialPops] = useStickyState<number[]>([
-    5, 5, 5, 5, 5, 5, 5, 5
-  ], "evolutio-initial-pops");
+  // keyed by strategy id; anything unset starts at 5
+  const [initialPops, setInitialPops] = useStickyState<Record<string, number>>({}, "evolutio-initial-pops-by-id");
 
   const activeTimeouts = useRef<number[]>([]);
 
@@ -52,7 +56,7 @@ function App() {
     if (evolutionData.length > 0) {
       const formattedData = evolutionData.map(gen => {
         const row: any = { Generation: gen.gen_number };
-        gen.populations.forEach((p: [string, number]) => {
+        gen.shares.forEach((p: [string, number]) => {
           row[p[0]] = p[1];
         });
         return row;
@@ -88,10 +92,10 @@ function App() {
     try {
       // 2. Call the Rust Backend
       // 'run_game' matches the function name in lib.rs
-      // { p1Id, p2Id, rounds } matches the arguments in Rust (p1_id, p2_id, rounds)
+      // p1 and p2 are strategy specs: a registry id, optionally with parameters like "joss(sneakiness=0.2)"
       const result = await invoke<MatchResult>("run_game", {
-        p1Id: p1Strategy,
-        p2Id: p2Strategy,
+        p1: p1Strategy,
+        p2: p2Strategy,
         rounds: rounds,
         noise: noise,
         payoffMatrix: payoff
@@ -115,7 +119,7 @@ function App() {
 
     } catch (error) {
       console.error("Rust Error:", error);
-      setLogs(prev => [...prev, `[CRITICAL ERROR] Kernel Panic: ${error}`]);
+      setLogs(prev => [...prev, `[CRITICAL ERROR] Kernel Panic: ${errorMessage(error)}`]);
     }
   };
 
@@ -136,6 +140,8 @@ function App() {
     try {
       interface TournamentResult {
         ranking: [string, number][]; // Tuple array
+        stats: { per_round: number }[]; // same order as ranking
+        cross_table: CrossTable;
       }
 
       const result = await invoke<TournamentResult>("run_tournament", {
@@ -144,7 +150,7 @@ function App() {
       });
 
       const rankLogs = result.ranking.map((entry, index) =>
-        `#${index + 1} ${entry[0].padEnd(18)}: ${entry[1]} pts`
+        `#${index + 1} ${entry[0].padEnd(18)}: ${entry[1]} pts (${result.stats[index].per_round.toFixed(2)}/round)`
       );
 
       setLogs(prev => [
@@ -158,10 +164,11 @@ function App() {
         score: entry[1]
       }));
       setTournamentData(chartData);
+      setCrossTable(result.cross_table);
 
     } catch (error) {
       console.error(error);
-      setLogs(prev => [...prev, `[ERROR] Tournament Failed: ${error}`]);
+      setLogs(prev => [...prev, `[ERROR] Tournament Failed: ${errorMessage(error)}`]);
     }
   };
 
@@ -178,20 +185,20 @@ function App() {
       ...prev,
       "==================================",
       "> STARTING EVOLUTIONARY CYCLE...",
-      "> Logic: Survival of the Fittest (Pop +/- 1 per Gen)",
+      "> Logic: Replicator dynamics (share grows with relative fitness)",
       `> Noise Level: ${(noise * 100).toFixed(0)}%`
     ]);
 
     try {
       interface Generation {
         gen_number: number;
-        populations: [string, number][];
+        shares: [string, number][];
       }
 
       const history = await invoke<Generation[]>("run_evolution", {
         rounds: rounds,
         noise: noise,
-        initialPopulations: initialPops,
+        initialPopulations: field.map(s => initialPops[s.id] ?? 5),
         generations: generations,
         payoffMatrix: payoff
       });
@@ -199,9 +206,9 @@ function App() {
       history.forEach((gen, index) => {
         const timerId = window.setTimeout(() => {
 
-          const alive = gen.populations
+          const alive = gen.shares
             .filter((p) => p[1] > 0)
-            .map(p => `${p[0]}: ${p[1]}`)
+            .map(p => `${p[0]}: ${(p[1] * 100).toFixed(1)}%`)
             .join(" | ");
           setLogs(prev => [...prev, `Gen ${gen.gen_number}: ${alive}`]);
           setEvolutionData(prev => [...prev, gen]);
@@ -213,7 +220,7 @@ function App() {
 
     } catch (error) {
       console.error(error);
-      setLogs(prev => [...prev, `[ERROR] Evolution Failed: ${error}`]);
+      setLogs(prev => [...prev, `[ERROR] Evolution Failed: ${errorMessage(error)}`]);
     }
   };
 
@@ -251,12 +258,14 @@ function App() {
           <div className="flex-1 overflow-y-auto flex flex-col gap-4 pr-2 pb-4 style-scrollbar">
             <StrategySelector
               label="Player 1 (The Hero)"
+              strategies={strategies}
               selectedId={p1Strategy}
               onChange={setP1Strategy}
             />
 
             <StrategySelector
               label="Player 2 (The Rival)"
+              strategies={strategies}
               selectedId={p2Strategy}
               onChange={setP2Strategy}
             />
@@ -275,7 +284,7 @@ function App() {
 
             <div className="p-4 bg-gray-800 rounded border border-gray-700 flex flex-col gap-2 shrink-0">
               <h3 className="text-xs font-bold text-gray-400 uppercase tracking-wider mb-2">Ecosystem Setup</h3>
-              {STRATEGIES.map((strategy, index) => (
+              {field.map((strategy) => (
                 <div key={strategy.id} className="flex justify-between items-center text-xs">
                   <Tooltip text={strategy.description}>
                     <span className={`border-b border-dashed border-gray-600 transition-colors pb-[1px] cursor-help ${strategy.color}`}>
@@ -286,12 +295,8 @@ function App() {
                     type="number"
                     min="0"
                     max="50"
-                    value={initialPops[index]}
-                    onChange={(e) => {
-                      const newPops = [...initialPops];
-                      newPops[index] = Number(e.target.value);
-                      setInitialPops(newPops);
-                    }}
+                    value={initialPops[strategy.id] ?? 5}
+                    onChange={(e) => setInitialPops({ ...initialPops, [strategy.id]: Number(e.target.value) })}
                     className="w-12 bg-gray-900 border border-gray-600 rounded text-center text-white focus:border-purple-500 outline-none"
                   />
                 </div>
@@ -369,7 +374,7 @@ function App() {
 
           {evolutionData.length > 0 && (() => {
             const latestGen = evolutionData[evolutionData.length - 1];
-            const pieData = latestGen.populations.map((p: [string, number]) => ({
+            const pieData = latestGen.shares.map((p: [string, number]) => ({
               name: p[0],
               value: p[1]
             }));
@@ -377,10 +382,10 @@ function App() {
             return (
               <div className="flex gap-4 items-start">
                 <div className="w-2/3">
-                  <EvolutionChart data={evolutionData} />
+                  <EvolutionChart data={evolutionData} colors={chartColors(strategies)} />
                 </div>
                 <div className="w-1/3">
-                  <DominanceChart data={pieData} generation={latestGen.gen_number} />
+                  <DominanceChart data={pieData} generation={latestGen.gen_number} colors={chartColors(strategies)} />
                 </div>
               </div>
             );
@@ -390,6 +395,10 @@ function App() {
             <TournamentChart data={tournamentData} />
           )}
 
+          {tournamentData.length > 0 && crossTable && (
+            <CrossTableHeatmap table={crossTable} />
+          )}
+
           <div className="mt-4 flex-1 overflow-y-auto">
             {logs.map((log, index) => (
               <TerminalLine key={index} text={log} />
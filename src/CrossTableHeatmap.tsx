@@ -0,0 +1,62 @@
+export interface CrossTable {
+    names: string[];
+    payoffs: number[][];
+    cooperation: number[][];
+    outcomes: { wins: number; losses: number; draws: number }[][];
+}
+
+interface Props {
+    table: CrossTable;
+}
+
+// red (exploited) -> green (mutual cooperation level and above)
+const cellColor = (value: number, min: number, max: number) => {
+    const t = max > min ? (value - min) / (max - min) : 0.5;
+    return `hsl(${Math.round(t * 120)}, 60%, ${20 + Math.round(t * 15)}%)`;
+};
+
+export function CrossTableHeatmap({ table }: Props) {
+    const values = table.payoffs.flat();
+    const min = Math.min(...values);
+    const max = Math.max(...values);
+
+    return (
+        <div className="w-full mt-4 bg-gray-900 border border-gray-700 rounded p-4 shadow-lg overflow-x-auto">
+            <h3 className="text-xs font-bold text-yellow-500 mb-2 tracking-widest uppercase border-b border-gray-800 pb-2">
+                Head-to-Head (mean payoff per round, row vs column)
+            </h3>
+            <table className="text-[10px] font-mono border-collapse">
+                <thead>
+                    <tr>
+                        <th />
+                        {table.names.map(name => (
+                            <th key={name} className="px-1 text-gray-400 font-normal whitespace-nowrap">{name}</th>
+                        ))}
+                    </tr>
+                </thead>
+                <tbody>
+                    {table.names.map((row, i) => (
+                        <tr key={row}>
+                            <th className="pr-2 text-right text-gray-400 font-normal whitespace-nowrap">{row}</th>
+                            {table.payoffs[i].map((payoff, j) => {
+                                const o = table.outcomes[i][j];
+                                return (
+                                    <td
+                                        key={j}
+                                        className="w-12 h-8 text-center text-white border border-gray-800"
+                                        style={{ backgroundColor: cellColor(payoff, min, max) }}
+                                        title={`${row} vs ${table.names[j]}: ${payoff.toFixed(2)} pts/round, ` +
+                                            `cooperates ${(table.cooperation[i][j] * 100).toFixed(0)}%, ` +
+                                            `W/L/D ${o.wins}/${o.losses}/${o.draws}`}
+                                    >
+                                        {payoff.toFixed(2)}
+                                    </td>
+                                );
+                            })}
+                        </tr>
+                    ))}
+                </tbody>
+            </table>
+        </div>
+    );
+}
@@ -1,22 +1,13 @@
 import { PieChart, Pie, Cell, ResponsiveContainer, Tooltip as RechartsTooltip } from "recharts";
+import { FALLBACK_HEX } from "./strategies";
 
 interface Props {
     data: { name: string; value: number }[];
     generation: number;
+    colors: Record<string, string>;
 }
 
-const COLOR_MAP: Record<string, string> = {
-    "Tit For Tat": "#60a5fa",    // blue-400
-    "Always Defect": "#ef4444",  // red-500
-    "Grim Trigger": "#a855f7",   // purple-500
-    "Always Cooperate": "#4ade80", // green-400
-    "Random": "#facc15",         // yellow-400
-    "Pavlov": "#f97316",         // orange-500
-    "Generous TFT": "#ec4899",   // pink-500
-    "Joss": "#f87171",           // red-400
-};
-
-export function DominanceChart({ data, generation }: Props) {
+export function DominanceChart({ data, generation, colors }: Props) {
     const activeData = data.filter(d => d.value > 0);
 
     return (
@@ -48,7 +39,7 @@ export function DominanceChart({ data, generation }: Props) {
                             {activeData.map((entry, index) => (
                                 <Cell
                                     key={`cell-${index}`}
-                                    fill={COLOR_MAP[entry.name] || "#ffffff"}
+                                    fill={colors[entry.name] ?? FALLBACK_HEX}
                                 />
                             ))}
                         </Pie>
@@ -1,37 +1,28 @@
 import { LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, Legend, ResponsiveContainer } from 'recharts';
-
-const STRATEGY_COLORS: Record<string, string> = {
-    "Tit-For-Tat": "#3b82f6", // Blue
-    "Always Defect": "#ef4444", // Red
-    "Grim Trigger": "#eab308", // Yellow
-    "Always Cooperate": "#22c55e", // Green
-    "Random": "#94a3b8", // Gray
-    "Pavlov": "#a855f7", // Purple
-    "Generous TFT": "#ec4899", // Pink
-    "Joss": "#f97316" // Orange
-};
+import { FALLBACK_HEX } from './strategies';
 
 interface GenerationData {
     gen_number: number;
-    populations: [string, number][];
+    shares: [string, number][];
 }
 
 interface Props {
     data: GenerationData[];
+    colors: Record<string, string>;
 }
 
-export function EvolutionChart({ data }: Props) {
+export function EvolutionChart({ data, colors }: Props) {
     if (!data || data.length === 0) return null;
 
     const chartData = data.map((gen) => {
         const dataPoint: any = { name: `Gen ${gen.gen_number}` };
-        gen.populations.forEach(([strategyName, count]) => {
-            dataPoint[strategyName] = count;
+        gen.shares.forEach(([strategyName, share]) => {
+            dataPoint[strategyName] = +(share * 100).toFixed(2);
         });
         return dataPoint;
     });
 
-    const strategyNames = data[0].populations.map(p => p[0]);
+    const strategyNames = data[0].shares.map(p => p[0]);
 
     return (
         <div className="w-full h-64 mt-4 bg-gray-900 border border-gray-700 rounded-lg p-2">
@@ -40,7 +31,7 @@ export function EvolutionChart({ data }: Props) {
                 <LineChart data={chartData} margin={{ top: 5, right: 20, left: 0, bottom: 5 }}>
                     <CartesianGrid strokeDasharray="3 3" stroke="#374151" />
                     <XAxis dataKey="name" stroke="#9ca3af" fontSize={10} tick={{ fill: '#9ca3af' }} />
-                    <YAxis stroke="#9ca3af" fontSize={10} tick={{ fill: '#9ca3af' }} />
+                    <YAxis stroke="#9ca3af" fontSize={10} tick={{ fill: '#9ca3af' }} unit="%" />
                     <Tooltip
                         contentStyle={{ backgroundColor: '#1f2937', border: '1px solid #374151', fontSize: '12px' }}
                         itemStyle={{ color: '#e5e7eb' }}
@@ -52,7 +43,7 @@ export function EvolutionChart({ data }: Props) {
                             key={name}
                             type="monotone"
                             dataKey={name}
-                            stroke={STRATEGY_COLORS[name] || "#ffffff"}
+                            stroke={colors[name] ?? FALLBACK_HEX}
                             strokeWidth={2}
                             dot={false}
                             activeDot={{ r: 4 }}
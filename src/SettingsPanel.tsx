@@ -1,3 +1,7 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage, PayoffWarning } from "./types";
+
 export interface Payoff {
     t: number;
     r: number;
@@ -28,6 +32,14 @@ const PRESETS = [
 export function SettingsPanel({
     speed, setSpeed, rounds, setRounds, noise, setNoise, generations, setGenerations, payoff, setPayoff
 }: Props) {
+    const [payoffWarnings, setPayoffWarnings] = useState<string[]>([]);
+
+    useEffect(() => {
+        invoke<PayoffWarning[]>("check_payoff_matrix", { payoffMatrix: payoff })
+            .then(warnings => setPayoffWarnings(warnings.map(w => w.message)))
+            .catch(error => setPayoffWarnings([errorMessage(error)]));
+    }, [payoff]);
+
     return (
         <div className="p-4 bg-gray-800 rounded border border-gray-700 flex flex-col gap-4">
             <h3 className="text-xs font-bold text-gray-400 uppercase tracking-wider">Simulation Parameters</h3>
@@ -111,6 +123,10 @@ export function SettingsPanel({
                         <span className="text-[9px] text-gray-500 uppercase tracking-tighter">Coop/Defect</span>
                     </div>
                 </div>
+
+                {payoffWarnings.map(warning => (
+                    <p key={warning} className="text-[9px] text-yellow-500 leading-tight">⚠ {warning}</p>
+                ))}
             </div>
 
             <div className="mt-2">
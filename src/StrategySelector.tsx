@@ -1,14 +1,16 @@
-import { STRATEGIES } from "./strategies";
+import { StrategyMeta } from "./strategies";
 import { Tooltip } from "./Tooltip";
 
 interface Props {
     label: string;
+    strategies: StrategyMeta[];
     selectedId: string;
     onChange: (id: string) => void;
 }
 
-export function StrategySelector({ label, selectedId, onChange }: Props) {
-    const current = STRATEGIES.find(s => s.id === selectedId) || STRATEGIES[0];
+export function StrategySelector({ label, strategies, selectedId, onChange }: Props) {
+    const current = strategies.find(s => s.id === selectedId) || strategies[0];
+    if (!current) return null;
 
     return (
         <div className="p-3 bg-gray-800 rounded border border-gray-600 transition-all hover:border-green-500 group relative">
@@ -31,7 +33,7 @@ export function StrategySelector({ label, selectedId, onChange }: Props) {
                 onChange={(e) => onChange(e.target.value)}
                 className="absolute inset-0 w-full h-full opacity-0 cursor-pointer z-10"
             >
-                {STRATEGIES.map(s => (
+                {strategies.map(s => (
                     <option key={s.id} value={s.id}>{s.name}</option>
                 ))}
             </select>
@@ -1,17 +1,62 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+export type StrategyTag = "nice" | "retaliatory" | "stochastic";
+
+export interface StrategyParam {
+  name: string;
+  default: number;
+  range: [number, number];
+  description: string;
+}
+
+// StrategyInfo from the Rust registry, plus the colour this UI draws it in
 export interface StrategyMeta {
   id: string;
   name: string;
   description: string;
+  tags: StrategyTag[];
+  // in the default tournament field and the evolution populations
+  default_field: boolean;
+  params: StrategyParam[];
+  // tailwind text class for labels, and the same colour as hex for charts
   color: string;
+  hex: string;
 }
 
-export const STRATEGIES: StrategyMeta[] = [
-  { id: "tit_for_tat", name: "Tit For Tat", description: "Starts with cooperation, then mimics your last move.", color: "text-blue-400" },
-  { id: "always_defect", name: "Always Defect", description: "The agent of chaos. Never cooperates.", color: "text-red-500" },
-  { id: "grim_trigger", name: "Grim Trigger", description: "Cooperates until you cross him ONCE. Then never forgives.", color: "text-purple-500" },
-  { id: "always_cooperate", name: "Always Cooperate", description: "The saint. Always cooperates.", color: "text-green-400" },
-  { id: "random", name: "Random", description: "Unpredictable. Flips a coin every time.", color: "text-yellow-400" },
-  { id: "pavlov", name: "Pavlov", description: "Win-Stay, Lose-Shift. Changes strategy only when it fails.", color: "text-orange-500" },
-  { id: "generous_tft", name: "Generous TFT", description: "Like TFT, but forgives mistakes with 10% probability to stop death spirals.", color: "text-pink-500" },
-  { id: "joss", name: "Joss", description: "Sneaky. Mostly TFT, but tries to defect 10% of the time to exploit you.", color: "text-red-400" },
-];
\ No newline at end of file
+// Presentation only: strategy data lives in the Rust registry (`list_strategies`).
+// Colours go by registry position, so a new strategy needs no entry here.
+const PALETTE: { text: string; hex: string }[] = [
+  { text: "text-blue-400", hex: "#60a5fa" },
+  { text: "text-red-500", hex: "#ef4444" },
+  { text: "text-purple-500", hex: "#a855f7" },
+  { text: "text-green-400", hex: "#4ade80" },
+  { text: "text-yellow-400", hex: "#facc15" },
+  { text: "text-orange-500", hex: "#f97316" },
+  { text: "text-pink-500", hex: "#ec4899" },
+  { text: "text-red-400", hex: "#f87171" },
+  { text: "text-cyan-400", hex: "#22d3ee" },
+  { text: "text-slate-400", hex: "#94a3b8" },
+];
+// for names the registry does not know, e.g. loaded machines
+export const FALLBACK_HEX = "#ffffff";
+
+// name -> chart colour, since simulation results are keyed by strategy name
+export function chartColors(strategies: StrategyMeta[]): Record<string, string> {
+  return Object.fromEntries(strategies.map(s => [s.name, s.hex]));
+}
+
+export function useStrategies(): StrategyMeta[] {
+  const [strategies, setStrategies] = useState<StrategyMeta[]>([]);
+
+  useEffect(() => {
+    invoke<Omit<StrategyMeta, "color" | "hex">[]>("list_strategies")
+      .then(list => setStrategies(list.map((s, i) => {
+        const { text, hex } = PALETTE[i % PALETTE.length];
+        return { ...s, color: text, hex };
+      })))
+      .catch(error => console.error("Failed to load strategy registry:", error));
+  }, []);
+
+  return strategies;
+}
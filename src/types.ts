@@ -8,4 +8,23 @@ export interface MatchResult {
   rounds: [Action, Action][];
   player_score: number;
   opponent_score: number;
-}
\ No newline at end of file
+  player_discounted_score: number | null;
+  opponent_discounted_score: number | null;
+}
+
+// EngineError, as returned by failing Rust commands
+export interface EngineError {
+  kind: string;
+  message: string;
+}
+
+// PayoffWarning, returned by check_payoff_matrix when the matrix is not a Prisoner's Dilemma
+export interface PayoffWarning {
+  kind: string;
+  message: string;
+}
+
+export const errorMessage = (error: unknown): string =>
+  typeof error === "object" && error !== null && "message" in error
+    ? String((error as EngineError).message)
+    : String(error);